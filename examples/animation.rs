@@ -0,0 +1,34 @@
+use std::time::Duration;
+use vui::*;
+
+fn main() {
+    let view = state(
+        || false,
+        |expanded, cx| {
+            let e = cx[expanded];
+            vstack((
+                button("Animate", move |cx| {
+                    with_animation(cx, Duration::from_millis(300), |cx| {
+                        cx[expanded] = !cx[expanded]
+                    })
+                }),
+                rectangle()
+                    .color(if e { RED_HIGHLIGHT } else { AZURE_HIGHLIGHT })
+                    .sized(if e { (200.0, 100.0) } else { (100.0, 100.0) })
+                    .opacity(if e { 1.0 } else { 0.5 }),
+                circle()
+                    .sized((30.0, 30.0))
+                    .offset((if e { 150.0 } else { 0.0 }, 0.0))
+                    .animation(Animation::spring(Duration::from_millis(400), 0.4), expanded),
+            ))
+            .padding(Auto)
+        },
+    );
+
+    App::new(view)
+        .title("Animation")
+        .size((400.0, 300.0))
+        .min_size((300.0, 250.0))
+        .run()
+        .unwrap();
+}
@@ -1,7 +1,4 @@
-use std::{
-    thread::{sleep, spawn},
-    time::Duration,
-};
+use std::time::Duration;
 use vui::*;
 
 fn main() {
@@ -9,12 +6,16 @@ fn main() {
         || "task not started".to_string(),
         |s, cx| {
             hstack((
-                button("press to begin", move |_| {
-                    spawn(move || {
-                        on_main(move |cx| cx[s] = "task started".into());
-                        sleep(Duration::from_secs(2));
-                        on_main(move |cx| cx[s] = "task complete".into());
-                    });
+                button("press to begin", move |cx| {
+                    cx[s] = "task started".into();
+                    cx.spawn(
+                        s,
+                        async {
+                            sleep(Duration::from_secs(2)).await;
+                            "task complete".to_string()
+                        },
+                        move |cx, result| cx[s] = result,
+                    );
                 }),
                 text(&cx[s]),
             ))
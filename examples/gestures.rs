@@ -24,6 +24,8 @@ fn main() {
         circle()
             .color(RED_HIGHLIGHT.alpha(0.8))
             .tap(|_cx| println!("tapped circle"))
+            .double_tap(|_cx| println!("double tapped circle"))
+            .long_press(|_cx, _| println!("long pressed circle"))
             .padding(Auto),
         // target offset
         state(MyState::default, move |s, cx| {
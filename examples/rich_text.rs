@@ -0,0 +1,32 @@
+use vui::*;
+
+fn main() {
+    vstack((
+        "Plain text in a color".color(AZURE_HIGHLIGHT),
+        rich_text([
+            span("Rich text can be "),
+            span("bold").bold(),
+            span(", "),
+            span("italic").italic(),
+            span(", "),
+            span("underlined").underline(),
+            span(", "),
+            span("struck out").strikethrough(),
+            span(", "),
+            span("spaced").letter_spacing(4.0),
+            span(", "),
+            span("monospaced").font_family("monospace"),
+            span(" or "),
+            span("colored").color(RED_HIGHLIGHT),
+            span("."),
+        ])
+        .font_size(18.0),
+        "Long labels can be cut short with an ellipsis instead of overflowing their cell."
+            .max_lines(1)
+            .truncation(Truncation::Middle),
+        "Centered text".text_align(TextAlign::Center),
+        text("Error 0x5f3a: select and copy me").selectable(),
+    ))
+    .padding(Auto)
+    .run()
+}
@@ -0,0 +1,492 @@
+use crate::*;
+use std::any::Any;
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+/// A value which can be interpolated, so changes to it can be animated.
+pub trait Animatable: Clone + PartialEq + 'static {
+    /// Returns the value a fraction `t` of the way from `self` to `to`.
+    ///
+    /// Springs overshoot, so `t` can be a little outside `0..=1`.
+    fn lerp(&self, to: &Self, t: f64) -> Self;
+}
+
+fn mix(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+impl Animatable for f64 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        mix(*self, *to, t)
+    }
+}
+
+impl Animatable for f32 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self + (to - self) * t as f32
+    }
+}
+
+impl Animatable for Vec2 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        *self + (*to - *self) * t
+    }
+}
+
+impl Animatable for Point {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        *self + (*to - *self) * t
+    }
+}
+
+impl Animatable for Size {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        Size::new(mix(self.width, to.width, t), mix(self.height, to.height, t))
+    }
+}
+
+impl Animatable for Rect {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        Rect::new(
+            mix(self.x0, to.x0, t),
+            mix(self.y0, to.y0, t),
+            mix(self.x1, to.x1, t),
+            mix(self.y1, to.y1, t),
+        )
+    }
+}
+
+impl Animatable for Color {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        let mut components = self.components;
+        for (c, to) in components.iter_mut().zip(to.components) {
+            *c = (mix(*c as f64, to as f64, t) as f32).clamp(0.0, 1.0);
+        }
+        Color::new(components)
+    }
+}
+
+/// How an animation moves from the old value to the new one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Curve {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A damped spring. `response` is the period of the undamped spring
+    /// in seconds, and `damping` runs from bouncy near 0 to no overshoot at 1.
+    Spring {
+        response: f64,
+        damping: f64,
+    },
+}
+
+impl Curve {
+    /// Eases `t`, the fraction of the animation's time which has passed.
+    pub fn ease(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Curve::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(2) / 2.0
+                }
+            }
+            Curve::Spring { response, damping } => {
+                let (omega, zeta) = spring_params(response, damping);
+                let time = t * spring_settle_time(omega, zeta);
+                if zeta < 1.0 {
+                    let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+                    let phase = omega_d * time;
+                    let envelope = (-zeta * omega * time).exp();
+                    1.0 - envelope * (phase.cos() + zeta * omega / omega_d * phase.sin())
+                } else {
+                    1.0 - (-omega * time).exp() * (1.0 + omega * time)
+                }
+            }
+        }
+    }
+}
+
+/// Natural frequency and damping ratio of a spring, kept in the range
+/// the easing formulas handle.
+fn spring_params(response: f64, damping: f64) -> (f64, f64) {
+    (TAU / response.max(0.01), damping.clamp(0.05, 1.0))
+}
+
+/// Seconds until a spring is close enough to rest to stop animating.
+fn spring_settle_time(omega: f64, zeta: f64) -> f64 {
+    // The envelope is within 1e-4 of the target, which is 1e-3 for
+    // a critically damped spring.
+    10000f64.ln() / (zeta * omega)
+}
+
+/// An easing curve and how long it runs, for `animation` and `with_animation`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Animation {
+    curve: Curve,
+    duration: f64,
+}
+
+impl Animation {
+    pub fn new(curve: Curve, duration: Duration) -> Self {
+        Self {
+            curve,
+            duration: duration.as_secs_f64(),
+        }
+    }
+
+    pub fn linear(duration: Duration) -> Self {
+        Self::new(Curve::Linear, duration)
+    }
+
+    pub fn ease_in(duration: Duration) -> Self {
+        Self::new(Curve::EaseIn, duration)
+    }
+
+    pub fn ease_out(duration: Duration) -> Self {
+        Self::new(Curve::EaseOut, duration)
+    }
+
+    pub fn ease_in_out(duration: Duration) -> Self {
+        Self::new(Curve::EaseInOut, duration)
+    }
+
+    /// A spring which runs until it comes to rest. See `Curve::Spring`.
+    pub fn spring(response: Duration, damping: f64) -> Self {
+        let response = response.as_secs_f64();
+        let (omega, zeta) = spring_params(response, damping);
+        Self {
+            curve: Curve::Spring { response, damping },
+            duration: spring_settle_time(omega, zeta),
+        }
+    }
+
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.duration)
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::ease_in_out(Duration::from_millis(250))
+    }
+}
+
+impl From<Duration> for Animation {
+    fn from(duration: Duration) -> Self {
+        Self::ease_in_out(duration)
+    }
+}
+
+/// Animates the changes `f` makes to state, rather than having
+/// views snap to their new colors, offsets, sizes and opacity.
+pub fn with_animation<R>(
+    cx: &mut Context,
+    animation: impl Into<Animation>,
+    f: impl FnOnce(&mut Context) -> R,
+) -> R {
+    cx.animator.transaction = Some((animation.into(), cx.animator.frame));
+    f(cx)
+}
+
+/// An animation set by the `animation` modifier for the views inside it.
+#[derive(Clone)]
+pub(crate) struct AnimationScope(pub Animation);
+
+/// A value moving between two others.
+struct Tween<T> {
+    from: T,
+    to: T,
+    start: f64,
+    animation: Option<Animation>,
+}
+
+impl<T: Animatable> Tween<T> {
+    fn still(value: T, now: f64) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            start: now,
+            animation: None,
+        }
+    }
+
+    fn value(&self, now: f64) -> T {
+        match self.animation {
+            Some(animation) if now < self.start + animation.duration => {
+                let t = (now - self.start) / animation.duration;
+                self.from.lerp(&self.to, animation.curve.ease(t))
+            }
+            _ => self.to.clone(),
+        }
+    }
+}
+
+/// A tween along with when it ends, so we can tell whether it's
+/// running without knowing its type.
+struct Track {
+    end: f64,
+    tween: Box<dyn Any>,
+}
+
+/// Tracks the values views are animating between.
+#[derive(Default)]
+pub(crate) struct Animator {
+    /// Tracks by view and property name.
+    tracks: HashMap<(ViewId, &'static str), Track>,
+
+    /// Counts calls to `Context::update`.
+    pub frame: u64,
+
+    /// When the current frame started.
+    pub now: f64,
+
+    /// When the previous frame started.
+    pub previous: f64,
+
+    /// The animation started by `with_animation`, and the frame it was started on.
+    pub transaction: Option<(Animation, u64)>,
+}
+
+impl Animator {
+    /// Changes are seen by the next layout or draw after the state changes,
+    /// which may be in the frame after, so animations stay in effect that long.
+    fn in_effect(&self, frame: u64) -> bool {
+        self.frame <= frame + 1
+    }
+
+    pub fn scope_in_effect(&self, frame: Option<u64>) -> bool {
+        frame.is_some_and(|frame| self.in_effect(frame))
+    }
+
+    pub fn animate<T: Animatable>(
+        &mut self,
+        id: ViewId,
+        property: &'static str,
+        target: T,
+        scope: Option<Animation>,
+    ) -> T {
+        let now = self.now;
+        let animation = scope.or(self
+            .transaction
+            .filter(|(_, frame)| self.in_effect(*frame))
+            .map(|(animation, _)| animation));
+
+        let track = self.tracks.entry((id, property)).or_insert_with(|| Track {
+            end: now,
+            tween: Box::new(Tween::still(target.clone(), now)),
+        });
+        let Some(tween) = track.tween.downcast_mut::<Tween<T>>() else {
+            *track = Track {
+                end: now,
+                tween: Box::new(Tween::still(target.clone(), now)),
+            };
+            return target;
+        };
+
+        if tween.to != target {
+            *tween = match animation {
+                Some(animation) => Tween {
+                    from: tween.value(now),
+                    to: target,
+                    start: now,
+                    animation: Some(animation),
+                },
+                None => Tween::still(target, now),
+            };
+            track.end = now + animation.map_or(0.0, |a| a.duration);
+        }
+
+        tween.value(now)
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.tracks.values().any(|track| track.end > self.now)
+    }
+
+    /// Whether this frame needs drawing for a tween, which includes the
+    /// frame after the last one it was moving in, so it's drawn at its end.
+    pub fn needs_frame(&self) -> bool {
+        self.tracks.values().any(|track| track.end > self.previous)
+    }
+
+    pub fn retain(&mut self, f: impl Fn(&ViewId) -> bool) {
+        self.tracks.retain(|(id, _), _| f(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_curves() {
+        let spring = Animation::spring(Duration::from_millis(300), 0.3);
+        for curve in [
+            Curve::Linear,
+            Curve::EaseIn,
+            Curve::EaseOut,
+            Curve::EaseInOut,
+            spring.curve(),
+        ] {
+            assert_eq!(curve.ease(0.0), 0.0);
+            assert!((curve.ease(1.0) - 1.0).abs() < 1e-3);
+        }
+        assert_eq!(Curve::EaseInOut.ease(0.5), 0.5);
+
+        // Bouncy springs overshoot, stiff ones don't.
+        let max = |curve: Curve| {
+            (0..100)
+                .map(|i| curve.ease(i as f64 / 100.0))
+                .fold(0.0, f64::max)
+        };
+        assert!(max(spring.curve()) > 1.1);
+        assert!(max(Animation::spring(Duration::from_millis(300), 1.0).curve()) <= 1.0);
+    }
+
+    #[test]
+    fn test_animate_several_properties() {
+        let mut animator = Animator::default();
+        let id = ViewId { id: 1 };
+        animator.animate(id, "width", 0.0, None);
+        animator.animate(id, "height", 0.0, None);
+        animator.animate(id, "color", BLACK, None);
+
+        let animation = Some(Animation::linear(Duration::from_secs(1)));
+        animator.animate(id, "width", 10.0, animation);
+        animator.animate(id, "height", 20.0, animation);
+        animator.animate(id, "color", WHITE, animation);
+
+        animator.now = 0.5;
+        assert_eq!(animator.animate(id, "width", 10.0, None), 5.0);
+        assert_eq!(animator.animate(id, "height", 20.0, None), 10.0);
+        assert_ne!(animator.animate(id, "color", WHITE, None), WHITE);
+        assert!(animator.is_animating());
+    }
+
+    #[test]
+    fn test_color_lerp() {
+        assert_eq!(BLACK.lerp(&WHITE, 0.0), BLACK);
+        assert_eq!(BLACK.lerp(&WHITE, 1.0), WHITE);
+        assert_eq!(BLACK.lerp(&WHITE, 1.5), WHITE);
+    }
+
+    fn sized_box(big: bool) -> SizeView<Rectangle> {
+        let size = if big { 100.0 } else { 50.0 };
+        rectangle().sized((size, size))
+    }
+
+    fn box_width(harness: &mut TestHarness<impl View>, path: &IdPath) -> f64 {
+        harness.layout();
+        harness.find_view_rect(path).unwrap().width()
+    }
+
+    #[test]
+    fn test_animation_modifier() {
+        let mut harness = TestHarness::new(
+            state(
+                || false,
+                |big, cx| {
+                    sized_box(cx[big]).animation(Animation::linear(Duration::from_millis(100)), big)
+                },
+            ),
+            (200.0, 200.0),
+        );
+        let big = harness.state_handle::<bool>(&vec![0]);
+        let rect_path = vec![0, 0, 0, 0];
+        assert_eq!(box_width(&mut harness, &rect_path), 50.0);
+
+        harness.cx_mut()[big] = true;
+        assert!(harness.update());
+        assert_eq!(box_width(&mut harness, &rect_path), 50.0);
+        assert!(harness.cx().is_animating());
+
+        harness.advance(0.05);
+        assert!((box_width(&mut harness, &rect_path) - 75.0).abs() < 1e-6);
+        assert!(harness.update());
+
+        harness.advance(0.05);
+        assert_eq!(box_width(&mut harness, &rect_path), 100.0);
+        assert!(!harness.cx().is_animating());
+        assert!(!harness.update());
+    }
+
+    #[test]
+    fn test_animation_end_is_drawn() {
+        let mut harness = TestHarness::new(
+            state(
+                || false,
+                |big, cx| {
+                    sized_box(cx[big]).animation(Animation::linear(Duration::from_millis(100)), big)
+                },
+            ),
+            (200.0, 200.0),
+        );
+        let big = harness.state_handle::<bool>(&vec![0]);
+        let rect_path = vec![0, 0, 0, 0];
+
+        harness.cx_mut()[big] = true;
+        let mut width = box_width(&mut harness, &rect_path);
+        for _ in 0..20 {
+            if !harness.update() {
+                break;
+            }
+            width = box_width(&mut harness, &rect_path);
+        }
+        assert_eq!(width, 100.0);
+    }
+
+    #[test]
+    fn test_changes_snap_without_animation() {
+        let mut harness = TestHarness::new(
+            state(|| false, |big, cx| sized_box(cx[big])),
+            (200.0, 200.0),
+        );
+        let big = harness.state_handle::<bool>(&vec![0]);
+        let rect_path = vec![0, 0, 0];
+        assert_eq!(box_width(&mut harness, &rect_path), 50.0);
+
+        harness.cx_mut()[big] = true;
+        harness.update();
+        assert_eq!(box_width(&mut harness, &rect_path), 100.0);
+        assert!(!harness.cx().is_animating());
+    }
+
+    #[test]
+    fn test_with_animation() {
+        let mut harness = TestHarness::new(
+            state(|| false, |big, cx| sized_box(cx[big])),
+            (200.0, 200.0),
+        );
+        let big = harness.state_handle::<bool>(&vec![0]);
+        let rect_path = vec![0, 0, 0];
+        assert_eq!(box_width(&mut harness, &rect_path), 50.0);
+
+        with_animation(harness.cx_mut(), Duration::from_millis(100), |cx| {
+            cx[big] = true
+        });
+        harness.update();
+        harness.advance(0.05);
+        let width = box_width(&mut harness, &rect_path);
+        assert!(width > 50.0 && width < 100.0);
+
+        harness.advance(0.05);
+        assert_eq!(box_width(&mut harness, &rect_path), 100.0);
+
+        // Later changes aren't animated.
+        harness.cx_mut()[big] = false;
+        harness.update();
+        assert_eq!(box_width(&mut harness, &rect_path), 50.0);
+    }
+}
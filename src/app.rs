@@ -0,0 +1,114 @@
+use crate::*;
+use vello::AaConfig;
+
+/// Configures the window a view runs in, then runs it.
+///
+/// ```no_run
+/// # use vui::*;
+/// App::new("Hello, world")
+///     .title("Hello")
+///     .size((400.0, 300.0))
+///     .logging(false)
+///     .run()
+///     .unwrap();
+/// ```
+pub struct App<V> {
+    pub(crate) view: V,
+    pub(crate) title: String,
+    pub(crate) size: Size,
+    pub(crate) min_size: Option<Size>,
+    pub(crate) resizable: bool,
+    pub(crate) background: Color,
+    pub(crate) antialiasing: AaConfig,
+    pub(crate) vsync: bool,
+    pub(crate) logging: bool,
+    pub(crate) dev_overlay: bool,
+    pub(crate) demo_keys: bool,
+}
+
+impl<V: View> App<V> {
+    pub fn new(view: V) -> Self {
+        Self {
+            view,
+            title: "vui".into(),
+            size: Size::new(1044.0, 800.0),
+            min_size: None,
+            resizable: true,
+            background: BLACK,
+            antialiasing: AaConfig::Area,
+            vsync: true,
+            logging: true,
+            dev_overlay: false,
+            demo_keys: false,
+        }
+    }
+
+    /// Sets the window title. A `window_title` modifier replaces it.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the initial size of the window, in points.
+    pub fn size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+
+    /// Stops the window being resized smaller than `size`, in points.
+    pub fn min_size(mut self, size: impl Into<Size>) -> Self {
+        self.min_size = Some(size.into());
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Sets the color drawn behind the view.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn antialiasing(mut self, antialiasing: AaConfig) -> Self {
+        self.antialiasing = antialiasing;
+        self
+    }
+
+    /// Waits for the display's refresh before showing each frame.
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Whether to log warnings to the terminal with `env_logger`. Turn this
+    /// off if your program sets up logging itself.
+    pub fn logging(mut self, logging: bool) -> Self {
+        self.logging = logging;
+        self
+    }
+
+    /// Shows frame rate, timings and other statistics over the view, for
+    /// tracking down slow frames.
+    pub fn dev_overlay(mut self, dev_overlay: bool) -> Self {
+        self.dev_overlay = dev_overlay;
+        self
+    }
+
+    /// Turns on keys for trying out the renderer while no view has the
+    /// keyboard focus: `s` shows the developer overlay's statistics, `d` its
+    /// frame time graph, `m` cycles antialiasing, `v` toggles vsync and
+    /// Escape quits.
+    pub fn demo_keys(mut self, demo_keys: bool) -> Self {
+        self.demo_keys = demo_keys;
+        self
+    }
+
+    /// Opens the window and runs the event loop until the window closes.
+    /// Returns an error if the window or event loop fails.
+    pub fn run(self) -> anyhow::Result<()> {
+        run_app(self)
+    }
+}
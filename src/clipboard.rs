@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Text on the clipboard, along with richer versions of it
+/// where the source application provided them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClipboardText {
+    pub text: String,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl From<&str> for ClipboardText {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
+impl From<String> for ClipboardText {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            html: None,
+            rtf: None,
+        }
+    }
+}
+
+/// Reads and writes the clipboard for `Context::clipboard_get_text`
+/// and `Context::clipboard_set_text`.
+///
+/// Install your own with `Context::set_clipboard`.
+pub trait Clipboard {
+    fn get_text(&self) -> Option<ClipboardText>;
+
+    fn set_text(&self, text: ClipboardText);
+}
+
+/// A clipboard which only lives in memory. Used by `TestHarness` and on
+/// platforms without a system clipboard.
+///
+/// Clones share their contents, so a test can keep one to inspect.
+#[derive(Clone, Default)]
+pub struct MemoryClipboard {
+    contents: Rc<RefCell<Option<ClipboardText>>>,
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&self) -> Option<ClipboardText> {
+        self.contents.borrow().clone()
+    }
+
+    fn set_text(&self, text: ClipboardText) {
+        *self.contents.borrow_mut() = Some(text);
+    }
+}
+
+/// The operating system's clipboard.
+///
+/// Connects on first use, and behaves as an empty clipboard if
+/// the connection fails.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+#[derive(Default)]
+pub struct SystemClipboard {
+    context: std::cell::OnceCell<Option<clipboard_rs::ClipboardContext>>,
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+impl SystemClipboard {
+    fn context(&self) -> Option<&clipboard_rs::ClipboardContext> {
+        self.context
+            .get_or_init(|| match clipboard_rs::ClipboardContext::new() {
+                Ok(context) => Some(context),
+                Err(err) => {
+                    log::warn!("error opening clipboard: {:?}", err);
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+impl Clipboard for SystemClipboard {
+    fn get_text(&self) -> Option<ClipboardText> {
+        use clipboard_rs::{Clipboard, ContentFormat};
+
+        let context = self.context()?;
+        let text = context.get_text().ok()?;
+        let html = context
+            .has(ContentFormat::Html)
+            .then(|| context.get_html().ok())
+            .flatten();
+        let rtf = context
+            .has(ContentFormat::Rtf)
+            .then(|| context.get_rich_text().ok())
+            .flatten();
+        Some(ClipboardText { text, html, rtf })
+    }
+
+    fn set_text(&self, text: ClipboardText) {
+        use clipboard_rs::{Clipboard, ClipboardContent};
+
+        let Some(context) = self.context() else {
+            return;
+        };
+        let mut contents = vec![ClipboardContent::Text(text.text)];
+        if let Some(html) = text.html {
+            contents.push(ClipboardContent::Html(html));
+        }
+        if let Some(rtf) = text.rtf {
+            contents.push(ClipboardContent::Rtf(rtf));
+        }
+        if let Err(err) = context.set(contents) {
+            log::warn!("error writing clipboard: {:?}", err);
+        }
+    }
+}
+
+/// The clipboard a new `Context` starts with.
+pub(crate) fn default_clipboard() -> Box<dyn Clipboard> {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    return Box::new(SystemClipboard::default());
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return Box::new(MemoryClipboard::default());
+}
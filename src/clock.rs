@@ -0,0 +1,56 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Tells the time for animations, through `Context::now` and
+/// `Context::delta_time`.
+///
+/// Install your own with `Context::set_clock`.
+pub trait Clock {
+    /// Seconds since some fixed point, such as when the clock was made.
+    fn now(&self) -> f64;
+}
+
+/// The default clock, which measures real time.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+/// A clock which only moves when told to, so tests can step
+/// animations deterministically.
+///
+/// Clones share their time, so a test can keep one to advance.
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<f64>>,
+}
+
+impl ManualClock {
+    pub fn advance(&self, seconds: f64) {
+        self.now.set(self.now.get() + seconds);
+    }
+
+    pub fn set(&self, now: f64) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+}
@@ -0,0 +1,146 @@
+use crate::*;
+use std::sync::Arc;
+
+/// Menu commands added with `command` and `command_group`, and their hotkeys.
+///
+/// The context collects them from the view tree whenever it changes. Hotkeys
+/// are pressed with the platform's modifier: command on macOS and control
+/// elsewhere.
+#[derive(Clone, Default)]
+pub struct CommandRegistry {
+    commands: Vec<CommandInfo>,
+}
+
+impl CommandRegistry {
+    /// Replaces the commands. Returns whether they changed.
+    pub(crate) fn set(&mut self, commands: Vec<CommandInfo>) -> bool {
+        if commands == self.commands {
+            return false;
+        }
+        self.commands = commands;
+        true
+    }
+
+    /// The commands, in the order the views declared them.
+    pub fn commands(&self) -> &[CommandInfo] {
+        &self.commands
+    }
+
+    /// Returns the command `key` triggers while `mods` are held, if any.
+    pub fn command_for_key(&self, key: Key, mods: KeyboardModifiers) -> Option<Arc<str>> {
+        let Key::Character(c) = key else {
+            return None;
+        };
+        if !platform_modifier(mods) || mods.shift || mods.alt {
+            return None;
+        }
+        let c = c.to_ascii_lowercase();
+        self.commands
+            .iter()
+            .find(|cmd| cmd.key.is_some_and(|hotkey| hotkey.char() == c))
+            .map(|cmd| cmd.path.clone())
+    }
+}
+
+/// Whether the modifier hotkeys are pressed with is held, and not the other one.
+fn platform_modifier(mods: KeyboardModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        mods.command && !mods.control
+    } else {
+        mods.control && !mods.command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn platform_mods() -> KeyboardModifiers {
+        if cfg!(target_os = "macos") {
+            KeyboardModifiers {
+                command: true,
+                ..Default::default()
+            }
+        } else {
+            KeyboardModifiers {
+                control: true,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_for_key() {
+        let mut registry = CommandRegistry::default();
+        assert!(registry.set(vec![
+            CommandInfo {
+                path: "File:New".into(),
+                key: Some(HotKey::KeyN),
+            },
+            CommandInfo {
+                path: "Edit:Two".into(),
+                key: None,
+            },
+        ]));
+        assert!(!registry.set(registry.commands().to_vec()));
+
+        let new = Some("File:New".into());
+        assert_eq!(
+            registry.command_for_key(Key::Character('n'), platform_mods()),
+            new
+        );
+        assert_eq!(
+            registry.command_for_key(Key::Character('N'), platform_mods()),
+            new
+        );
+        assert_eq!(
+            registry.command_for_key(Key::Character('n'), Default::default()),
+            None
+        );
+        let shift = KeyboardModifiers {
+            shift: true,
+            ..platform_mods()
+        };
+        assert_eq!(registry.command_for_key(Key::Character('n'), shift), None);
+        assert_eq!(
+            registry.command_for_key(Key::Character('t'), platform_mods()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hotkeys_dispatch_commands() {
+        let ui = state(Vec::<&str>::new, |log, _| {
+            rectangle()
+                .command("File:New", Some(HotKey::KeyN), move |cx| {
+                    cx[log].push("new")
+                })
+                .command_group((command("File:Open").hotkey(HotKey::KeyO),))
+                .key(move |cx, _| cx[log].push("key"))
+        });
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let paths: Vec<_> = harness
+            .cx()
+            .command_registry()
+            .commands()
+            .iter()
+            .map(|cmd| (cmd.path.to_string(), cmd.key))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("File:New".to_string(), Some(HotKey::KeyN)),
+                ("File:Open".to_string(), Some(HotKey::KeyO)),
+            ]
+        );
+
+        harness.cx_mut().key_mods = platform_mods();
+        harness.key(Key::Character('n'));
+        harness.cx_mut().key_mods = Default::default();
+        harness.key(Key::Character('n'));
+
+        let log = harness.state_handle::<Vec<&str>>(&vec![0]);
+        assert_eq!(harness.cx()[log], ["new", "key"]);
+    }
+}
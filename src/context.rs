@@ -2,10 +2,12 @@ use crate::*;
 use parley::FontContext;
 use std::any::Any;
 use std::any::TypeId;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
 use std::iter::FromIterator;
 use std::ops;
 use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender, channel};
 use vello::Scene;
 
 #[derive(Clone, Eq, PartialEq)]
@@ -16,6 +18,12 @@ pub struct CommandInfo {
 
 pub const DEBUG_LAYOUT: bool = false;
 
+/// Time step for the first frame, before there's one to measure from.
+const DEFAULT_FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// Longest time step, so animations don't jump after a pause.
+const MAX_FRAME_TIME: f64 = 0.25;
+
 #[derive(Copy, Clone, Default, PartialEq, Debug)]
 pub(crate) struct LayoutBox {
     pub rect: Rect,
@@ -34,16 +42,21 @@ pub(crate) type EnvMap = HashMap<TypeId, Box<dyn Any>>;
 /// The Context stores all UI state. A user of the library
 /// shouldn't have to interact with it directly.
 pub struct Context {
-    /// Layout information for all views.
-    layout: HashMap<IdPath, LayoutBox>,
+    /// Layout information for all views. Ordered by path, so
+    /// the views below a path are next to each other.
+    layout: BTreeMap<IdPath, LayoutBox>,
 
-    /// Allocated ViewIds.
-    view_ids: HashMap<IdPath, ViewId>,
+    /// Allocated ViewIds, ordered by path like `layout`.
+    view_ids: BTreeMap<IdPath, ViewId>,
 
     /// Next allocated id.
     next_id: ViewId,
 
+    /// Ids of forgotten views, to reuse before allocating new ones.
+    free_ids: Vec<ViewId>,
+
     /// Which views each touch (or mouse pointer) is interacting with.
+    /// Index 0 is the mouse, and fingers take the other slots.
     pub(crate) touches: [ViewId; 16],
 
     /// Points at which touches (or click-drags) started.
@@ -61,15 +74,36 @@ pub struct Context {
     /// Keyboard modifiers state.
     pub key_mods: KeyboardModifiers,
 
+    /// `hover` views the mouse is currently inside.
+    pub(crate) hovered: HashSet<ViewId>,
+
+    /// Gesture recognizers competing for touches.
+    pub(crate) gestures: GestureArenas,
+
+    /// Set when a view has handled the current event, so views
+    /// further out can ignore it. Used to route scrolling to the
+    /// innermost scrollable view.
+    pub(crate) event_handled: bool,
+
     /// The view that has the keyboard focus.
     pub(crate) focused_id: Option<ViewId>,
 
+    /// Text view accepting input method events, and the area in
+    /// window coordinates the candidate window should avoid.
+    pub(crate) ime_area: Option<(ViewId, Rect)>,
+
     /// The current title of the window
     pub window_title: Arc<str>,
 
     /// Are we fullscreen?
     pub fullscreen: bool,
 
+    /// Menu commands in the view tree.
+    pub(crate) command_registry: CommandRegistry,
+
+    /// Set by `quit` to stop the event loop.
+    pub(crate) quit_requested: bool,
+
     /// User state created by `state`.
     pub(crate) state_map: StateMap,
 
@@ -88,6 +122,10 @@ pub struct Context {
     /// A stack of ids for states to get parent dependencies.
     pub(crate) id_stack: Vec<ViewId>,
 
+    /// The visible part of the content being laid out by a scroll view,
+    /// in the content's coordinates.
+    pub(crate) viewport: Option<Rect>,
+
     /// Previous window size.
     window_size: Size,
 
@@ -100,6 +138,51 @@ pub struct Context {
     pub(crate) prev_grab_cursor: bool,
 
     pub font_ctx: FontContext,
+
+    /// Runs futures started by `spawn`.
+    executor: Box<dyn Executor>,
+
+    /// Used for cut, copy and paste.
+    clipboard: Box<dyn Clipboard>,
+
+    /// Tells the time for animations.
+    clock: Box<dyn Clock>,
+
+    /// When the last animation frame ran.
+    last_frame: Option<f64>,
+
+    /// Time between the last two animation frames.
+    delta_time: f64,
+
+    /// Values views are animating between.
+    pub(crate) animator: Animator,
+
+    /// Clock time by which a view wants another frame, from `request_frame`.
+    next_frame: Option<f64>,
+
+    /// Frames drawn by `render`.
+    frames_drawn: u64,
+
+    /// Pixels per point of the window, from the last `render`.
+    scale_factor: f64,
+
+    /// Seconds the last `render` spent laying out and drawing,
+    /// for the developer overlay.
+    pub(crate) layout_time: f64,
+    pub(crate) draw_time: f64,
+
+    /// Views kept by the last garbage collection.
+    pub(crate) live_views: usize,
+
+    /// Tasks which haven't delivered their results, by serial number.
+    pub(crate) tasks: HashMap<u64, Task>,
+
+    /// Serial number for the next task.
+    next_task: u64,
+
+    /// Finished tasks send their results here.
+    task_sender: Sender<(u64, Box<dyn Any + Send>)>,
+    task_receiver: Receiver<(u64, Box<dyn Any + Send>)>,
 }
 
 impl Default for Context {
@@ -110,55 +193,114 @@ impl Default for Context {
 
 impl Context {
     pub fn new() -> Self {
+        let (task_sender, task_receiver) = channel();
         Self {
-            layout: HashMap::new(),
-            view_ids: HashMap::new(),
-            next_id: ViewId { id: 0 },
+            layout: BTreeMap::new(),
+            view_ids: BTreeMap::new(),
+            // Start at 1 so no view gets the default id, which
+            // marks unused touches.
+            next_id: ViewId { id: 1 },
+            free_ids: vec![],
             touches: [ViewId::default(); 16],
             starts: [Point::ZERO; 16],
             previous_position: [Point::ZERO; 16],
             mouse_button: None,
             mouse_buttons: Default::default(),
             key_mods: Default::default(),
+            hovered: HashSet::new(),
+            gestures: GestureArenas::default(),
+            event_handled: false,
             focused_id: None,
+            ime_area: None,
             window_title: "vui".into(),
             fullscreen: false,
+            command_registry: CommandRegistry::default(),
+            quit_requested: false,
             state_map: HashMap::new(),
             dirty: false,
             enable_dirty: true,
             env: HashMap::new(),
             deps: HashMap::new(),
             id_stack: vec![],
+            viewport: None,
             window_size: Size::default(),
             root_offset: Vec2::ZERO,
             grab_cursor: false,
             prev_grab_cursor: false,
             font_ctx: FontContext::new(),
+            executor: Box::new(ThreadExecutor),
+            clipboard: default_clipboard(),
+            clock: Box::new(SystemClock::default()),
+            last_frame: None,
+            delta_time: DEFAULT_FRAME_TIME,
+            animator: Animator::default(),
+            next_frame: None,
+            frames_drawn: 0,
+            scale_factor: 1.0,
+            layout_time: 0.0,
+            draw_time: 0.0,
+            live_views: 0,
+            tasks: HashMap::new(),
+            next_task: 0,
+            task_sender,
+            task_receiver,
         }
     }
 
     /// Call this after the event queue is cleared.
+    ///
+    /// Returns whether the UI needs to be drawn again: when state has
+    /// changed, a value is animating, a frame was requested with
+    /// `request_frame`, or the window has been resized.
     pub fn update(&mut self, view: &impl View, window_size: Size) -> bool {
         // If the window size has changed, force a relayout.
-        if window_size != self.window_size {
+        let resized = window_size != self.window_size;
+        if resized {
             self.deps.clear();
             self.window_size = window_size;
         }
 
+        self.deliver_task_results();
+
         let mut path = vec![0];
 
         // Run any animations.
+        let now = self.clock.now();
+        self.delta_time = match self.last_frame {
+            Some(last) => (now - last).clamp(0.0, MAX_FRAME_TIME),
+            None => DEFAULT_FRAME_TIME,
+        };
+        self.last_frame = Some(now);
+        self.animator.frame += 1;
+        self.animator.previous = self.animator.now;
+        self.animator.now = now;
+
+        // Views ask again for frames they still need while animating, and
+        // requests for later stay until they're due.
+        let frame_due = self.next_frame.is_some_and(|time| time <= now);
+        if frame_due {
+            self.next_frame = None;
+        }
         let mut actions = vec![];
         view.process(&Event::Anim, &mut path, self, &mut actions);
         assert!(path.len() == 1);
 
-        if self.dirty {
+        if self.dirty || resized {
+            let mut commands = vec![];
+            self.commands(view, &mut commands);
+            if self.command_registry.set(commands) {
+                log::debug!("{} menu commands", self.command_registry.commands().len());
+            }
+        }
+
+        let redraw = if self.dirty {
             // Clean up state and layout.
             let mut keep = vec![];
             view.gc(&mut path, self, &mut keep);
             assert!(path.len() == 1);
             let keep_set = HashSet::<ViewId>::from_iter(keep);
-            self.state_map.retain(|k, _| keep_set.contains(k));
+            self.live_views = keep_set.len();
+            self.retain_views(|k| keep_set.contains(k));
 
             let mut new_layout = self.layout.clone();
             new_layout.retain(|k, _| keep_set.contains(&self.view_id(k)));
@@ -178,16 +320,31 @@ impl Context {
 
             true
         } else {
-            false
+            resized || frame_due
+        };
+
+        // Only time spent animating counts towards `delta_time`, so the
+        // first frame after the UI has been idle doesn't jump ahead.
+        let animating = self.animator.needs_frame()
+            || self.next_frame.is_some_and(|time| time <= self.clock.now());
+        if !animating {
+            self.last_frame = None;
         }
+
+        redraw || animating
     }
 
     /// Redraw the UI
-    pub fn render(&mut self, view: &impl View, window_size: Size, _scale: f32) -> Scene {
+    ///
+    /// Layout happens in points, at `window_size`, and the scene is
+    /// scaled by `scale` pixels per point.
+    pub fn render(&mut self, view: &impl View, window_size: Size, scale: f64) -> Scene {
+        self.scale_factor = scale;
         let mut path = vec![0];
         // Disable dirtying the state during layout and rendering
         // to avoid constantly re-rendering if some state is saved.
         self.enable_dirty = false;
+        let start = self.clock.now();
         let _ = view.layout(
             &mut path,
             &mut LayoutArgs {
@@ -200,14 +357,47 @@ impl Context {
         // Center the root view in the window.
         self.root_offset = Vec2::ZERO;
 
+        let laid_out = self.clock.now();
         let scene = view.draw(&mut path, self);
         self.enable_dirty = true;
+        self.layout_time = laid_out - start;
+        self.draw_time = self.clock.now() - laid_out;
+        self.frames_drawn += 1;
+
+        if scale == 1.0 {
+            return scene;
+        }
+        let mut scaled = Scene::new();
+        scaled.append(&scene, Some(kurbo::Affine::scale(scale)));
+        scaled
+    }
+
+    /// Lets views handle the user asking to close the window. Returns
+    /// whether to close it, which is unless a `close_request` modifier
+    /// kept it open without calling `quit`.
+    pub(crate) fn close_requested(&mut self, view: &impl View) -> bool {
+        self.process(view, &Event::CloseRequested);
+        !self.event_handled || self.quit_requested
+    }
 
-        scene
+    /// Processes a key press. Hotkeys for menu commands send the
+    /// command instead, and mark the event handled.
+    pub(crate) fn process_key(&mut self, view: &impl View, key: Key) {
+        match self.command_registry.command_for_key(key, self.key_mods) {
+            Some(command) => {
+                self.process(view, &Event::Command(command));
+                self.event_handled = true;
+            }
+            None => self.process(view, &Event::Key(key)),
+        }
     }
 
     /// Process a UI event
     pub fn process(&mut self, view: &impl View, event: &Event) {
+        self.event_handled = false;
+        if let Event::TouchBegin { id, .. } = event {
+            self.gestures.touch_began(*id);
+        }
         let mut actions = vec![];
         let mut path = vec![0];
         view.process(
@@ -224,19 +414,200 @@ impl Context {
         }
     }
 
+    /// Menu commands in the view tree, as of the last update.
+    pub fn command_registry(&self) -> &CommandRegistry {
+        &self.command_registry
+    }
+
     /// Get menu commands.
     pub fn commands(&mut self, view: &impl View, cmds: &mut Vec<CommandInfo>) {
         let mut path = vec![0];
         view.commands(&mut path, self, cmds);
     }
 
+    /// Runs `future` on the executor and passes its result to `f` on the main thread.
+    ///
+    /// The future is cancelled if the `state` view which owns `owner` goes away.
+    pub fn spawn<S: 'static, T: Send + 'static>(
+        &mut self,
+        owner: StateHandle<S>,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl FnOnce(&mut Context, T) + 'static,
+    ) {
+        self.spawn_task(owner.id, future, f)
+    }
+
+    /// Replaces the executor used by `spawn` and the `task` modifier.
+    pub fn set_executor(&mut self, executor: impl Executor + 'static) {
+        self.executor = Box::new(executor);
+    }
+
+    /// Returns the text on the clipboard, with rich formats where available.
+    pub fn clipboard_get_text(&self) -> Option<ClipboardText> {
+        self.clipboard.get_text()
+    }
+
+    /// Puts text on the clipboard. Pass a `ClipboardText` to include rich formats.
+    pub fn clipboard_set_text(&mut self, text: impl Into<ClipboardText>) {
+        self.clipboard.set_text(text.into())
+    }
+
+    /// Seconds since the clock started.
+    pub fn now(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Seconds since the last animation frame. This is what the
+    /// `anim` modifier passes to its callback.
+    pub fn delta_time(&self) -> f64 {
+        self.delta_time
+    }
+
+    /// Asks for another frame as soon as possible, so `Event::Anim` is
+    /// processed again. Otherwise the UI only updates when something
+    /// happens, so an idle app doesn't use the CPU or GPU.
+    ///
+    /// Requests last until the frame they asked for, so views which animate
+    /// by hand ask again while processing each `Event::Anim` until they're done.
+    pub fn request_frame(&mut self) {
+        self.request_frame_after(0.0);
+    }
+
+    /// Asks for a frame once `seconds` have passed, such as for the next
+    /// blink of a caret. Like `request_frame`, this lasts until that frame.
+    pub fn request_frame_after(&mut self, seconds: f64) {
+        let time = self.clock.now() + seconds.max(0.0);
+        self.next_frame = Some(self.next_frame.map_or(time, |next| next.min(time)));
+    }
+
+    /// Seconds until the frame asked for with `request_frame`, if any.
+    pub fn next_frame_in(&self) -> Option<f64> {
+        self.next_frame.map(|time| (time - self.now()).max(0.0))
+    }
+
+    /// Pixels per point of the window. Positions and sizes in views are
+    /// all in points, so this is only needed to line things up with pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Closes the window and stops the app once the current event is handled.
+    pub fn quit(&mut self) {
+        self.quit_requested = true;
+    }
+
+    /// How many frames `render` has drawn. This stays put while the
+    /// UI is idle.
+    pub fn frames_drawn(&self) -> u64 {
+        self.frames_drawn
+    }
+
+    /// Replaces the clock used for animations, for example with a
+    /// `ManualClock` in tests.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+        self.last_frame = None;
+    }
+
+    /// Returns the value view `id` should show for `property`, which is
+    /// changing to `target`. Changes inside `with_animation` or an
+    /// `animation` modifier move there over time, and others snap.
+    ///
+    /// Custom views call this when drawing or laying out, once per property
+    /// and with a different name for each.
+    pub fn animate<T: Animatable>(&mut self, id: ViewId, property: &'static str, target: T) -> T {
+        let scope = self
+            .env
+            .get(&TypeId::of::<AnimationScope>())
+            .map(|scope| scope.downcast_ref::<AnimationScope>().unwrap().0);
+        self.animator.animate(id, property, target, scope)
+    }
+
+    /// Are any values still moving towards their targets?
+    pub fn is_animating(&self) -> bool {
+        self.animator.is_animating()
+    }
+
+    /// Adds the fonts in a TTF, OTF or collection file, and returns the
+    /// names of their families for use with `font_family` and `DefaultFont`.
+    ///
+    /// Embed the file with `include_bytes!` so text looks the same on
+    /// machines which don't have the font installed.
+    pub fn register_font(&mut self, bytes: impl Into<Vec<u8>>) -> Vec<String> {
+        let collection = &mut self.font_ctx.collection;
+        let mut names = vec![];
+        for (family, _) in collection.register_fonts(bytes.into()) {
+            if let Some(name) = collection
+                .family_name(family)
+                .filter(|name| !names.iter().any(|n| n == name))
+            {
+                names.push(name.to_string());
+            }
+        }
+
+        // Text may now lay out differently.
+        self.set_dirty();
+        names
+    }
+
+    /// Replaces the clipboard used by `clipboard_get_text` and `clipboard_set_text`.
+    pub fn set_clipboard(&mut self, clipboard: impl Clipboard + 'static) {
+        self.clipboard = Box::new(clipboard);
+    }
+
+    pub(crate) fn spawn_task<T: Send + 'static>(
+        &mut self,
+        owner: ViewId,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl FnOnce(&mut Context, T) + 'static,
+    ) {
+        let serial = self.next_task;
+        self.next_task += 1;
+
+        let handle = TaskHandle::default();
+        self.tasks.insert(
+            serial,
+            Task {
+                owner,
+                handle: handle.clone(),
+                deliver: Box::new(move |cx, value| f(cx, *value.downcast::<T>().unwrap())),
+            },
+        );
+
+        let sender = self.task_sender.clone();
+        let future = Cancellable {
+            future: Box::pin(future),
+            handle,
+        };
+        self.executor.spawn(Box::pin(async move {
+            if let Some(value) = future.await {
+                if sender.send((serial, Box::new(value))).is_ok() {
+                    wake_event_loop();
+                }
+            }
+        }));
+    }
+
+    /// Passes the results of finished tasks to their callbacks.
+    fn deliver_task_results(&mut self) {
+        while let Ok((serial, value)) = self.task_receiver.try_recv() {
+            // Cancelled tasks have already been removed.
+            if let Some(task) = self.tasks.remove(&serial) {
+                (task.deliver)(self, value);
+            }
+        }
+    }
+
     pub(crate) fn view_id(&mut self, path: &IdPath) -> ViewId {
         match self.view_ids.get_mut(path) {
             Some(id) => *id,
             None => {
-                let id = self.next_id;
+                let id = self.free_ids.pop().unwrap_or_else(|| {
+                    let id = self.next_id;
+                    self.next_id.id += 1;
+                    id
+                });
                 self.view_ids.insert(path.clone(), id);
-                self.next_id.id += 1;
                 id
             }
         }
@@ -249,6 +620,106 @@ impl Context {
         }
     }
 
+    /// Returns the layout rect of the view at `path`, translated by
+    /// the offsets recorded along the path.
+    pub(crate) fn layout_rect(&self, path: &IdPath) -> Option<Rect> {
+        let rect = self.layout.get(path)?.rect;
+        let mut offset = Vec2::ZERO;
+        for i in 1..=path.len() {
+            if let Some(b) = self.layout.get(&path[..i]) {
+                offset += b.offset;
+            }
+        }
+        Some(rect + offset)
+    }
+
+    /// Drops the state kept for views which `keep` rejects.
+    fn retain_views(&mut self, keep: impl Fn(&ViewId) -> bool) {
+        self.state_map.retain(|k, _| keep(k));
+        self.hovered.retain(|k| keep(k));
+        self.gestures.retain(|k| keep(k));
+        self.animator.retain(|k| keep(k));
+        if self.ime_area.is_some_and(|(id, _)| !keep(&id)) {
+            self.ime_area = None;
+        }
+        self.tasks.retain(|_, task| {
+            let alive = keep(&task.owner);
+            if !alive {
+                task.handle.cancel();
+            }
+            alive
+        });
+    }
+
+    /// Forgets the view at `path` and everything below it, so views which
+    /// are created on demand don't accumulate. Their ids are reused for
+    /// new views, so everything kept for them goes too.
+    pub(crate) fn forget_subtree(&mut self, path: &[u64]) {
+        let below = |map_path: &&IdPath| map_path.starts_with(path);
+        let paths: Vec<IdPath> = self
+            .layout
+            .range(path.to_vec()..)
+            .map(|(k, _)| k)
+            .take_while(below)
+            .cloned()
+            .collect();
+        for k in paths {
+            self.layout.remove(&k);
+        }
+
+        let paths: Vec<IdPath> = self
+            .view_ids
+            .range(path.to_vec()..)
+            .map(|(k, _)| k)
+            .take_while(below)
+            .cloned()
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+        let gone: HashSet<ViewId> = paths
+            .iter()
+            .filter_map(|k| self.view_ids.remove(k))
+            .collect();
+
+        self.retain_views(|id| !gone.contains(id));
+        self.deps.retain(|id, _| !gone.contains(id));
+        if self.focused_id.is_some_and(|id| gone.contains(&id)) {
+            self.focused_id = None;
+        }
+        for touch in &mut self.touches {
+            if gone.contains(touch) {
+                *touch = ViewId::default();
+            }
+        }
+        self.free_ids.extend(gone);
+    }
+
+    /// Gives touch `id` to the view `vid`, unless another view has it.
+    pub(crate) fn capture_touch(&mut self, id: usize, vid: ViewId) -> bool {
+        if self.touches[id].is_default() || self.touches[id] == vid {
+            self.touches[id] = vid;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the view `vid` has touch `id`.
+    pub(crate) fn has_touch(&self, id: usize, vid: ViewId) -> bool {
+        self.touches[id] == vid
+    }
+
+    /// Frees touch `id` if the view `vid` has it, returning whether it did.
+    pub(crate) fn release_touch(&mut self, id: usize, vid: ViewId) -> bool {
+        if self.touches[id] == vid {
+            self.touches[id] = ViewId::default();
+            true
+        } else {
+            false
+        }
+    }
+
     pub(crate) fn update_layout(&mut self, path: &IdPath, layout_box: LayoutBox) {
         match self.layout.get_mut(path) {
             Some(bref) => *bref = layout_box,
@@ -326,6 +797,30 @@ impl Context {
         old_value
     }
 
+    /// Puts back the value `set_env` replaced, or removes the
+    /// value if there wasn't one, so it doesn't leak to siblings.
+    pub(crate) fn restore_env<S: Clone + 'static>(&mut self, old: Option<S>) {
+        match old {
+            Some(value) => {
+                self.set_env(&value);
+            }
+            None => {
+                self.env.remove(&TypeId::of::<S>());
+            }
+        }
+    }
+
+    /// Mutable access to state without marking it dirty, for views
+    /// which keep bookkeeping in the state map.
+    pub(crate) fn state_mut<S: 'static>(&mut self, id: ViewId) -> &mut S {
+        self.state_map
+            .get_mut(&id)
+            .unwrap()
+            .state
+            .downcast_mut()
+            .unwrap()
+    }
+
     pub fn get<S>(&self, id: StateHandle<S>) -> &S
     where
         S: 'static,
@@ -0,0 +1,164 @@
+use crate::*;
+use std::collections::VecDeque;
+use vello::AaConfig;
+use vello::kurbo::{Affine, Line, Stroke};
+
+/// Frames of history in the frame time graph.
+const GRAPH_FRAMES: usize = 120;
+
+/// Frame time at the top of the graph.
+const GRAPH_MAX_TIME: f64 = 1.0 / 20.0;
+
+/// Frame time the graph marks with a line. Slower frames are drawn in red.
+const TARGET_FRAME_TIME: f64 = 1.0 / 60.0;
+
+const PANEL_WIDTH: f64 = 240.0;
+const PANEL_PADDING: f64 = 8.0;
+const FONT_SIZE: f32 = 12.0;
+const LINE_HEIGHT: f64 = 16.0;
+const GRAPH_HEIGHT: f64 = 40.0;
+
+/// Timings shown by the developer overlay, in seconds.
+#[derive(Default)]
+pub(crate) struct FrameStats {
+    /// Times between recent frames, oldest first.
+    frame_times: VecDeque<f64>,
+
+    /// When the last frame was drawn.
+    last_frame: Option<f64>,
+
+    /// Time spent in `Context::update`.
+    pub update: f64,
+
+    /// Time spent rendering on the GPU and presenting the last frame.
+    pub submit: f64,
+}
+
+impl FrameStats {
+    /// Records that a frame is being drawn at `now`.
+    pub fn frame(&mut self, now: f64) {
+        if let Some(last) = self.last_frame {
+            if self.frame_times.len() == GRAPH_FRAMES {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back(now - last);
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// Mean time between recent frames.
+    fn mean_frame_time(&self) -> Option<f64> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        Some(self.frame_times.iter().sum::<f64>() / self.frame_times.len() as f64)
+    }
+
+    /// Draws the overlay in the top left corner of the window, in points.
+    pub fn draw(&self, cx: &mut Context, antialiasing: AaConfig, show_graph: bool) -> Scene {
+        let ms = |seconds: f64| seconds * 1000.0;
+        let fps = match self.mean_frame_time() {
+            Some(mean) if mean > 0.0 => format!("{:.0} fps  {:.1} ms", 1.0 / mean, ms(mean)),
+            _ => "-- fps".to_string(),
+        };
+        let lines = [
+            fps,
+            format!(
+                "update {:.2} ms  submit {:.2} ms",
+                ms(self.update),
+                ms(self.submit)
+            ),
+            format!(
+                "layout {:.2} ms  draw {:.2} ms",
+                ms(cx.layout_time),
+                ms(cx.draw_time)
+            ),
+            format!("{} views  {} states", cx.live_views, cx.state_map.len()),
+            format!("antialiasing {:?}", antialiasing),
+        ];
+
+        let text_height = LINE_HEIGHT * lines.len() as f64;
+        let graph_height = if show_graph {
+            GRAPH_HEIGHT + PANEL_PADDING
+        } else {
+            0.0
+        };
+        let panel = Rect::new(
+            0.0,
+            0.0,
+            PANEL_WIDTH,
+            text_height + graph_height + 2.0 * PANEL_PADDING,
+        );
+
+        let mut scene = Scene::new();
+        scene.fill(
+            peniko::Fill::NonZero,
+            Affine::IDENTITY,
+            BLACK.multiply_alpha(0.75),
+            None,
+            &panel,
+        );
+
+        for (i, line) in lines.iter().enumerate() {
+            let offset = Vec2::new(PANEL_PADDING, PANEL_PADDING + LINE_HEIGHT * i as f64);
+            scene.append(
+                &draw_text(line, FONT_SIZE, offset, None, WHITE, &mut cx.font_ctx),
+                None,
+            );
+        }
+
+        if show_graph {
+            let graph = Rect::from_origin_size(
+                (PANEL_PADDING, PANEL_PADDING * 2.0 + text_height),
+                (PANEL_WIDTH - 2.0 * PANEL_PADDING, GRAPH_HEIGHT),
+            );
+            self.draw_graph(&mut scene, graph);
+        }
+
+        scene
+    }
+
+    /// Draws a bar for each recent frame's time.
+    fn draw_graph(&self, scene: &mut Scene, graph: Rect) {
+        let bar_width = graph.width() / GRAPH_FRAMES as f64;
+        let height = |time: f64| (time / GRAPH_MAX_TIME).min(1.0) * graph.height();
+
+        for (i, time) in self.frame_times.iter().enumerate() {
+            let x = graph.x0 + bar_width * i as f64;
+            let bar = Rect::new(x, graph.y1 - height(*time), x + bar_width, graph.y1);
+            let color = if *time > TARGET_FRAME_TIME * 1.2 {
+                RED_HIGHLIGHT
+            } else {
+                GREEN_HIGHLIGHT
+            };
+            scene.fill(peniko::Fill::NonZero, Affine::IDENTITY, color, None, &bar);
+        }
+
+        let target = graph.y1 - height(TARGET_FRAME_TIME);
+        scene.stroke(
+            &Stroke::new(1.0),
+            Affine::IDENTITY,
+            MEDIUM_GRAY,
+            None,
+            &Line::new((graph.x0, target), (graph.x1, target)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_frame_stats() {
+        let mut stats = FrameStats::default();
+        assert_eq!(stats.mean_frame_time(), None);
+
+        for i in 0..=GRAPH_FRAMES + 10 {
+            stats.frame(i as f64 / 50.0);
+        }
+        assert_eq!(stats.frame_times.len(), GRAPH_FRAMES);
+        assert!((stats.mean_frame_time().unwrap() - 0.02).abs() < 1e-9);
+    }
+}
@@ -27,6 +27,31 @@ pub enum Event {
         position: Point,
     },
 
+    /// Mouse moved, whether or not a button is down.
+    PointerMove { position: Point, delta: Vec2 },
+
+    /// Mouse wheel or trackpad scroll. Line deltas are converted to points,
+    /// and positive values move content right and down.
+    Scroll {
+        position: Point,
+        delta: Vec2,
+        phase: GestureState,
+    },
+
+    /// Trackpad pinch. `delta` is the change in scale, positive when zooming in.
+    Pinch {
+        position: Point,
+        delta: f64,
+        phase: GestureState,
+    },
+
+    /// Trackpad rotation. `delta` is in radians, positive counter-clockwise.
+    Rotate {
+        position: Point,
+        delta: f64,
+        phase: GestureState,
+    },
+
     /// Called when the mouse gets outside the window
     MouseLeftWindow,
 
@@ -39,8 +64,22 @@ pub enum Event {
     /// Key released.
     KeyReleased(Key),
 
+    /// The input method is composing text, which isn't final yet. `cursor`
+    /// is the byte range selected within `text`, or `None` to hide the caret.
+    /// An empty `text` ends composition.
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+
+    /// The input method has finished composing text.
+    ImeCommit(String),
+
     /// Animation.
     Anim,
+
+    /// The user asked to close the window, for example with its close button.
+    CloseRequested,
 }
 
 impl Event {
@@ -50,6 +89,10 @@ impl Event {
             Event::TouchBegin { position, .. } => *position += offset,
             Event::TouchMove { position, .. } => *position += offset,
             Event::TouchEnd { position, .. } => *position += offset,
+            Event::PointerMove { position, .. } => *position += offset,
+            Event::Scroll { position, .. } => *position += offset,
+            Event::Pinch { position, .. } => *position += offset,
+            Event::Rotate { position, .. } => *position += offset,
             _ => (),
         }
         event
@@ -144,3 +187,10 @@ pub enum HotKey {
     KeyY,
     KeyZ,
 }
+
+impl HotKey {
+    /// The lowercase letter for the hotkey.
+    pub fn char(self) -> char {
+        (b'a' + self as u8) as char
+    }
+}
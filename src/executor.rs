@@ -0,0 +1,210 @@
+use crate::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// A future which can be sent to an `Executor`.
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Runs futures started by `Context::spawn` and the `task` modifier.
+///
+/// Install your own with `Context::set_executor` to run tasks on an
+/// existing async runtime.
+pub trait Executor {
+    fn spawn(&self, future: BoxFuture);
+}
+
+/// The default executor, which runs each future to completion on its own thread.
+#[derive(Clone, Copy, Default)]
+pub struct ThreadExecutor;
+
+impl Executor for ThreadExecutor {
+    fn spawn(&self, future: BoxFuture) {
+        std::thread::spawn(move || pollster::block_on(future));
+    }
+}
+
+#[derive(Default)]
+struct TaskShared {
+    cancelled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Shared between a running future and the `Context` so the future can be cancelled.
+#[derive(Clone, Default)]
+pub(crate) struct TaskHandle {
+    shared: Arc<TaskShared>,
+}
+
+impl TaskHandle {
+    pub(crate) fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.shared.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Wraps a future so it resolves to `None` as soon as its handle is cancelled.
+pub(crate) struct Cancellable<F> {
+    pub(crate) future: Pin<Box<F>>,
+    pub(crate) handle: TaskHandle,
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        *self.handle.shared.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        // Check after storing the waker so a concurrent cancel isn't missed.
+        if self.handle.is_cancelled() {
+            return Poll::Ready(None);
+        }
+
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// A task which hasn't delivered its result yet.
+pub(crate) struct Task {
+    /// The task is cancelled when this view is garbage collected.
+    pub(crate) owner: ViewId,
+    pub(crate) handle: TaskHandle,
+    pub(crate) deliver: Box<dyn FnOnce(&mut Context, Box<dyn std::any::Any + Send>)>,
+}
+
+/// Returns a future which completes after `duration`.
+///
+/// This doesn't depend on any runtime, so it works with the default executor.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now() + duration,
+        waker: None,
+    }
+}
+
+/// Future returned by `sleep`.
+pub struct Sleep {
+    deadline: Instant,
+    waker: Option<Arc<Mutex<Waker>>>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        match &self.waker {
+            Some(waker) => *waker.lock().unwrap() = cx.waker().clone(),
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                let deadline = self.deadline;
+                let thread_waker = waker.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    thread_waker.lock().unwrap().wake_by_ref();
+                });
+                self.waker = Some(waker);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Holds futures so the test decides when they run.
+    #[derive(Clone, Default)]
+    struct ManualExecutor {
+        futures: Rc<RefCell<Vec<BoxFuture>>>,
+    }
+
+    impl Executor for ManualExecutor {
+        fn spawn(&self, future: BoxFuture) {
+            self.futures.borrow_mut().push(future);
+        }
+    }
+
+    impl ManualExecutor {
+        /// Polls every future once, returning how many completed.
+        fn poll_all(&self) -> usize {
+            let mut cx = std::task::Context::from_waker(Waker::noop());
+            let mut futures = self.futures.borrow_mut();
+            let before = futures.len();
+            futures.retain_mut(|f| f.as_mut().poll(&mut cx).is_pending());
+            before - futures.len()
+        }
+    }
+
+    #[test]
+    fn test_spawn_delivers_result() {
+        let ui = state(
+            || 0,
+            |s, _| {
+                rectangle().tap(move |cx| {
+                    cx.spawn(s, async { 42 }, move |cx, value| cx[s] = value);
+                })
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let executor = ManualExecutor::default();
+        harness.cx_mut().set_executor(executor.clone());
+        let s = harness.state_handle::<i32>(&vec![0]);
+
+        harness.tap_at((50.0, 50.0));
+        assert_eq!(harness.cx()[s], 0);
+
+        assert_eq!(executor.poll_all(), 1);
+        harness.update();
+        assert_eq!(harness.cx()[s], 42);
+        assert!(harness.cx().tasks.is_empty());
+    }
+
+    #[test]
+    fn test_task_cancelled_on_gc() {
+        let ui = state(
+            || true,
+            |show, cx| {
+                cond(
+                    cx[show],
+                    rectangle().task(std::future::pending::<()>, |_, _| ()),
+                    rectangle(),
+                )
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let executor = ManualExecutor::default();
+        harness.cx_mut().set_executor(executor.clone());
+        let show = harness.state_handle::<bool>(&vec![0]);
+
+        harness.update();
+        assert_eq!(harness.cx().tasks.len(), 1);
+        assert_eq!(executor.poll_all(), 0);
+
+        // Starting once is enough.
+        harness.update();
+        assert_eq!(harness.cx().tasks.len(), 1);
+
+        harness.cx_mut()[show] = false;
+        harness.update();
+        assert!(harness.cx().tasks.is_empty());
+        assert_eq!(executor.poll_all(), 1);
+    }
+}
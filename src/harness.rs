@@ -0,0 +1,409 @@
+use crate::*;
+
+/// Time each update moves the harness clock on.
+const HARNESS_FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// Drives a view without a window or GPU, for tests.
+///
+/// The harness owns a `Context` and a fixed window size. Events are
+/// injected the same way the winit loop does, and `Context::update`
+/// runs after each one so layout stays current. Time is simulated:
+/// each update is a frame of 1/60 s on a `ManualClock`.
+///
+/// Paths passed to the harness start at the root view, so the root
+/// is `vec![0]` and its first child is usually `vec![0, 0]`.
+pub struct TestHarness<V> {
+    view: V,
+    cx: Context,
+    window_size: Size,
+    scale_factor: f64,
+    mouse_position: Point,
+    touch_positions: [Point; 16],
+    clock: ManualClock,
+}
+
+impl<V: View> TestHarness<V> {
+    /// Creates a harness and lays the view out once.
+    pub fn new(view: V, window_size: impl Into<Size>) -> Self {
+        let mut harness = Self {
+            view,
+            cx: Context::new(),
+            window_size: window_size.into(),
+            scale_factor: 1.0,
+            mouse_position: Point::ZERO,
+            touch_positions: [Point::ZERO; 16],
+            clock: ManualClock::default(),
+        };
+        harness.cx.set_clock(harness.clock.clone());
+        // Keep tests off the system clipboard.
+        harness.cx.set_clipboard(MemoryClipboard::default());
+        harness.layout();
+        harness
+    }
+
+    pub fn cx(&self) -> &Context {
+        &self.cx
+    }
+
+    pub fn cx_mut(&mut self) -> &mut Context {
+        &mut self.cx
+    }
+
+    pub fn window_size(&self) -> Size {
+        self.window_size
+    }
+
+    /// Sets the pixels per point `render` draws at, like a HiDPI window.
+    /// Layout and events stay in points.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// Changes the window size and lays the view out again.
+    pub fn resize(&mut self, window_size: impl Into<Size>) {
+        self.window_size = window_size.into();
+        self.update();
+        self.layout();
+    }
+
+    /// Runs `Context::update`, as the event loop does after each batch of events.
+    /// Each update is a frame, 1/60 s after the last.
+    pub fn update(&mut self) -> bool {
+        self.clock.advance(HARNESS_FRAME_TIME);
+        self.cx.update(&self.view, self.window_size)
+    }
+
+    /// Forces a layout pass.
+    pub fn layout(&mut self) -> Size {
+        let mut path = vec![0];
+        let size = self.view.layout(
+            &mut path,
+            &mut LayoutArgs {
+                size: self.window_size,
+                ctx: &mut self.cx,
+            },
+        );
+        assert_eq!(path.len(), 1);
+        size
+    }
+
+    /// Lays out and draws the view, returning the scene without rasterizing it.
+    pub fn render(&mut self) -> Scene {
+        self.cx
+            .render(&self.view, self.window_size, self.scale_factor)
+    }
+
+    /// Lets `seconds` of animation frames go by.
+    pub fn advance(&mut self, seconds: f64) {
+        let frames = (seconds / HARNESS_FRAME_TIME).ceil() as usize;
+        for _ in 0..frames {
+            self.update();
+        }
+    }
+
+    /// Lets `seconds` go by without any frames, as when the app is idle.
+    pub fn idle(&mut self, seconds: f64) {
+        self.clock.advance(seconds);
+    }
+
+    /// Processes an event and updates.
+    pub fn process(&mut self, event: &Event) {
+        self.cx.process(&self.view, event);
+        self.update();
+    }
+
+    /// Moves the mouse to `position`, with or without a button down.
+    pub fn mouse_move(&mut self, position: impl Into<Point>) {
+        let position = position.into();
+        let delta = position - self.mouse_position;
+        self.mouse_position = position;
+        self.process(&Event::PointerMove { position, delta });
+    }
+
+    /// Presses the left mouse button at `position`.
+    pub fn mouse_down(&mut self, position: impl Into<Point>) {
+        let position = position.into();
+        if position != self.mouse_position {
+            self.mouse_move(position);
+        }
+        self.cx.mouse_button = Some(MouseButton::Left);
+        self.cx.mouse_buttons.left = true;
+        self.process(&Event::TouchBegin { id: 0, position });
+    }
+
+    /// Releases the left mouse button at `position`.
+    pub fn mouse_up(&mut self, position: impl Into<Point>) {
+        let position = position.into();
+        if position != self.mouse_position {
+            self.mouse_move(position);
+        }
+        self.cx.mouse_button = None;
+        self.cx.mouse_buttons.left = false;
+        self.process(&Event::TouchEnd { id: 0, position });
+    }
+
+    /// Clicks at `position`.
+    pub fn tap_at(&mut self, position: impl Into<Point>) {
+        let position = position.into();
+        self.mouse_down(position);
+        self.mouse_up(position);
+    }
+
+    /// Puts finger `id` down at `position`. Fingers use ids 1 to 15,
+    /// since 0 is the mouse.
+    pub fn touch_begin(&mut self, id: usize, position: impl Into<Point>) {
+        let position = position.into();
+        self.touch_positions[id] = position;
+        self.process(&Event::TouchBegin { id, position });
+    }
+
+    /// Moves finger `id` to `position`.
+    pub fn touch_move(&mut self, id: usize, position: impl Into<Point>) {
+        let position = position.into();
+        let delta = position - self.touch_positions[id];
+        self.touch_positions[id] = position;
+        self.process(&Event::TouchMove {
+            id,
+            position,
+            delta,
+        });
+    }
+
+    /// Lifts finger `id` at `position`.
+    pub fn touch_end(&mut self, id: usize, position: impl Into<Point>) {
+        let position = position.into();
+        self.touch_positions[id] = position;
+        self.process(&Event::TouchEnd { id, position });
+    }
+
+    /// Drags from `from` to `to` in `steps` moves.
+    pub fn drag(&mut self, from: impl Into<Point>, to: impl Into<Point>, steps: usize) {
+        let from = from.into();
+        let to = to.into();
+        let steps = steps.max(1);
+        self.mouse_down(from);
+        for i in 1..=steps {
+            self.mouse_move(from.lerp(to, i as f64 / steps as f64));
+        }
+        self.mouse_up(to);
+    }
+
+    /// Scrolls the mouse wheel at `position`.
+    pub fn scroll(&mut self, position: impl Into<Point>, delta: impl Into<Vec2>) {
+        self.process(&Event::Scroll {
+            position: position.into(),
+            delta: delta.into(),
+            phase: GestureState::Changed,
+        });
+    }
+
+    /// Presses and releases a key.
+    pub fn key(&mut self, key: Key) {
+        self.cx.process_key(&self.view, key);
+        self.update();
+        self.process(&Event::KeyReleased(key));
+    }
+
+    /// Types a string one key at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let key = match c {
+                ' ' => Key::Space,
+                '\n' => Key::Enter,
+                '\t' => Key::Tab,
+                c => Key::Character(c),
+            };
+            self.key(key);
+        }
+    }
+
+    /// Asks to close the window, like its close button does. Returns
+    /// whether the window would close.
+    pub fn request_close(&mut self) -> bool {
+        let close = self.cx.close_requested(&self.view);
+        self.update();
+        close
+    }
+
+    /// Returns the rect of the view at `path` in window coordinates,
+    /// or `None` if that view hasn't recorded a layout.
+    ///
+    /// Offsets are accumulated from the views along the path which record them
+    /// (stacks and lists), so views inside `offset` or `padding` are not shifted.
+    pub fn find_view_rect(&self, path: &IdPath) -> Option<Rect> {
+        self.cx.layout_rect(path)
+    }
+
+    /// Returns the id of the view at `path`.
+    pub fn view_id(&mut self, path: &IdPath) -> ViewId {
+        self.cx.view_id(path)
+    }
+
+    /// Returns a handle to the state created by the `state` view at `path`.
+    pub fn state_handle<S: 'static>(&mut self, path: &IdPath) -> StateHandle<S> {
+        StateHandle::new(self.cx.view_id(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_harness_tap() {
+        let ui = state(
+            || 0,
+            |count, _| {
+                rectangle().tap(move |cx| {
+                    cx[count] += 1;
+                })
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<i32>(&vec![0]);
+
+        harness.tap_at((50.0, 50.0));
+        harness.tap_at((50.0, 50.0));
+
+        assert_eq!(harness.cx()[s], 2);
+    }
+
+    #[test]
+    fn test_harness_type_text() {
+        let ui = state(String::new, |s, _| {
+            rectangle().key(move |cx, key| {
+                match key {
+                    Key::Character(c) => cx[s].push(c),
+                    Key::Space => cx[s].push(' '),
+                    _ => (),
+                };
+            })
+        });
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        harness.type_text("hi there");
+
+        assert_eq!(harness.cx()[s], "hi there");
+    }
+
+    #[test]
+    fn test_harness_find_view_rect() {
+        let ui = hstack((
+            rectangle().sized((10.0, 10.0)),
+            rectangle().sized((20.0, 20.0)),
+        ));
+        let harness = TestHarness::new(ui, (100.0, 100.0));
+
+        assert_eq!(
+            harness.find_view_rect(&vec![0, 0, 0]),
+            Some(Rect::new(0.0, 5.0, 10.0, 15.0))
+        );
+        assert_eq!(
+            harness.find_view_rect(&vec![0, 1, 0]),
+            Some(Rect::new(10.0, 0.0, 30.0, 20.0))
+        );
+        assert_eq!(harness.find_view_rect(&vec![0, 2]), None);
+    }
+
+    #[test]
+    fn test_redraw_on_demand() {
+        let ui = state(|| 0, |count, _| rectangle().tap(move |cx| cx[count] += 1));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+
+        // The first update sees the window size.
+        assert!(harness.update());
+        assert!(!harness.update());
+        assert_eq!(harness.cx().next_frame_in(), None);
+
+        harness.tap_at((50.0, 50.0));
+        assert!(!harness.update());
+
+        harness.cx_mut().request_frame();
+        assert!(harness.update());
+        assert!(!harness.update());
+        assert_eq!(harness.cx().next_frame_in(), None);
+
+        harness.render();
+        assert_eq!(harness.cx().frames_drawn(), 1);
+    }
+
+    #[test]
+    fn test_anim_requests_frames() {
+        let mut harness = TestHarness::new(rectangle().anim(|_, _| ()), (100.0, 100.0));
+        for _ in 0..3 {
+            assert!(harness.update());
+            assert_eq!(harness.cx().next_frame_in(), Some(0.0));
+        }
+    }
+
+    #[test]
+    fn test_request_frame_after() {
+        let mut harness = TestHarness::new(rectangle(), (100.0, 100.0));
+        harness.update();
+        harness.idle(5.0);
+
+        // The request waits out frames before it's due, then lasts a frame.
+        harness.cx_mut().request_frame_after(0.5);
+        let frames: Vec<bool> = (0..40).map(|_| harness.update()).collect();
+        assert_eq!(frames.iter().filter(|&&drawn| drawn).count(), 1);
+        assert!(!frames[..29].contains(&true));
+        assert_eq!(harness.cx().next_frame_in(), None);
+    }
+
+    #[test]
+    fn test_delta_time_after_idle() {
+        // Time the UI sat idle doesn't count as a frame.
+        let mut harness = TestHarness::new(rectangle(), (100.0, 100.0));
+        harness.update();
+        harness.idle(5.0);
+        harness.update();
+        assert_eq!(harness.cx().delta_time(), HARNESS_FRAME_TIME);
+
+        // Slow frames while animating do.
+        let mut harness = TestHarness::new(rectangle().anim(|_, _| ()), (100.0, 100.0));
+        harness.update();
+        harness.idle(0.1);
+        harness.update();
+        assert!((harness.cx().delta_time() - (0.1 + HARNESS_FRAME_TIME)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_render_scale_factor() {
+        let ui = hstack((rectangle().sized((10.0, 10.0)), rectangle()));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let rect = harness.find_view_rect(&vec![0, 1]);
+
+        // Views still lay out in points on a HiDPI window.
+        harness.set_scale_factor(2.0);
+        harness.render();
+        assert_eq!(harness.cx().scale_factor(), 2.0);
+        assert_eq!(harness.find_view_rect(&vec![0, 1]), rect);
+        assert_eq!(harness.window_size(), Size::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_close_request() {
+        let mut harness = TestHarness::new(rectangle(), (100.0, 100.0));
+        assert!(harness.request_close());
+
+        let ui = state(
+            || 0,
+            |count, _| {
+                rectangle().close_request(move |cx| {
+                    cx[count] += 1;
+                    // Close when asked a second time.
+                    if cx[count] == 2 {
+                        cx.quit();
+                    }
+                })
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        assert!(!harness.request_close());
+        assert!(!harness.cx().quit_requested);
+        assert!(harness.request_close());
+        assert!(harness.cx().quit_requested);
+    }
+}
@@ -1,17 +1,37 @@
 mod align;
 pub use align::*;
+mod animator;
+pub use animator::*;
+mod app;
+pub use app::*;
 mod binding;
 pub use binding::*;
+mod clipboard;
+pub use clipboard::*;
+mod clock;
+pub use clock::*;
 mod colors;
 pub use colors::*;
+mod command_registry;
+pub use command_registry::*;
 mod context;
 pub use context::*;
+mod dev_overlay;
+pub(crate) use dev_overlay::*;
 mod event;
 pub use event::*;
+mod executor;
+pub use executor::*;
+mod harness;
+pub use harness::*;
 mod lens;
 pub use lens::*;
 mod modifiers;
 pub use modifiers::*;
+mod offscreen;
+pub use offscreen::*;
+mod snapshot;
+pub use snapshot::*;
 mod view;
 pub use view::*;
 mod views;
@@ -21,7 +41,8 @@ pub use viewid::*;
 mod viewtuple;
 pub use viewtuple::*;
 mod winit_loop;
-pub use winit_loop::vui;
+pub use winit_loop::{on_main, vui};
+pub(crate) use winit_loop::{run_app, wake_event_loop};
 
 pub use vello::{
     self, Scene,
@@ -38,6 +59,6 @@ where
     T: View,
 {
     fn run(self) {
-        vui(self).expect("Expected no error");
+        App::new(self).run().expect("Expected no error");
     }
 }
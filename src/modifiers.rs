@@ -1,13 +1,24 @@
 use crate::*;
+use std::future::Future;
 use std::marker::PhantomData;
 
 /// Modifiers common to all views.
 pub trait Modifiers: View + Sized {
-    /// Calls a closure after rendering with context and delta time.
+    /// Calls a closure every frame with the context and the seconds since the last frame.
     fn anim<F: Fn(&mut Context, f64) + 'static + Clone>(self, func: F) -> AnimView<Self, F> {
         AnimView::new(self, func)
     }
 
+    /// Animates changes to the colors, offsets, sizes and opacity of the
+    /// views inside when `value` changes, rather than having them snap.
+    fn animation<T: Clone + PartialEq + 'static, B: Binding<T>>(
+        self,
+        animation: impl Into<Animation>,
+        value: B,
+    ) -> AnimationView<Self, B, T> {
+        AnimationView::new(self, animation.into(), value)
+    }
+
     /// Puts a view behind another. The background view inherits the size of the view.
     fn background<BG: View + Clone>(self, background: BG) -> Background<Self, BG> {
         Background::new(self, background)
@@ -44,6 +55,15 @@ pub trait Modifiers: View + Sized {
         Drag::new(self, DragFuncP { f })
     }
 
+    /// Calls a function in response to a drag. Version which passes the index
+    /// of the touch (0 for the mouse), for views which follow several fingers.
+    fn drag_t<F: Fn(&mut Context, usize, Vec2, GestureState) + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Drag<Self, DragFuncT<F>> {
+        Drag::new(self, DragFuncT { f })
+    }
+
     /// Calls a function in response to a drag. Version which passes in a binding.
     fn drag_s<
         T: Clone + 'static,
@@ -116,11 +136,25 @@ pub trait Modifiers: View + Sized {
         Offset::new(self, offset.into())
     }
 
+    /// Draws a view partially transparent, from 0 (invisible) to 1 (opaque).
+    fn opacity(self, opacity: f64) -> Opacity<Self> {
+        Opacity::new(self, opacity)
+    }
+
     /// Adds space around a view. Can be either `Auto` or `Px(number_of_pixels)`
     fn padding(self, param: impl Into<PaddingParam>) -> Padding<Self> {
         Padding::new(self, param.into())
     }
 
+    /// Calls a function when the mouse wheel or trackpad scrolls over the view.
+    /// Only the innermost `scroll` under the pointer is called.
+    fn scroll<A: 'static, F: Fn(&mut Context, Vec2) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> ScrollGesture<Self, F> {
+        ScrollGesture::new(self, f)
+    }
+
     /// Constrains the size of a view.
     fn sized(self, size: impl Into<Size>) -> SizeView<Self> {
         SizeView::new(self, size.into())
@@ -131,13 +165,13 @@ pub trait Modifiers: View + Sized {
         self,
         f: F,
     ) -> Tap<Self, TapAdapter<F>> {
-        Tap::new(self, TapAdapter { f })
+        Gesture::new(self, TapRecognizer::new(1, TapAdapter { f }))
     }
 
     /// Version of `tap` which takes an action type instead
     /// of a function.
     fn tap_a<A: Clone + 'static>(self, action: A) -> Tap<Self, TapActionAdapter<A>> {
-        Tap::new(self, TapActionAdapter { action })
+        Gesture::new(self, TapRecognizer::new(1, TapActionAdapter { action }))
     }
 
     /// Version of `tap` which passes the tap position and mouse button.
@@ -145,7 +179,77 @@ pub trait Modifiers: View + Sized {
         self,
         f: F,
     ) -> Tap<Self, TapPositionFunc<F>> {
-        Tap::new(self, TapPositionFunc { f })
+        Gesture::new(self, TapRecognizer::new(1, TapPositionFunc { f }))
+    }
+
+    /// Calls a function in response to a double tap. A `tap` on the same
+    /// view only fires once the double tap has failed.
+    fn double_tap<A: 'static, F: Fn(&mut Context) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Tap<Self, TapAdapter<F>> {
+        Gesture::new(self, TapRecognizer::new(2, TapAdapter { f }))
+    }
+
+    /// Calls a function in response to a triple tap.
+    fn triple_tap<A: 'static, F: Fn(&mut Context) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Tap<Self, TapAdapter<F>> {
+        Gesture::new(self, TapRecognizer::new(3, TapAdapter { f }))
+    }
+
+    /// Calls a function, with where the touch began, when a touch
+    /// is held in place.
+    fn long_press<A: 'static, F: Fn(&mut Context, Point) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Gesture<Self, LongPressRecognizer<F>> {
+        Gesture::new(self, LongPressRecognizer::new(f))
+    }
+
+    /// Calls a function in response to a quick flick.
+    fn swipe<A: 'static, F: Fn(&mut Context, SwipeDirection) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Gesture<Self, SwipeRecognizer<F>> {
+        Gesture::new(self, SwipeRecognizer::new(f))
+    }
+
+    /// Calls a function in response to a two-finger or trackpad pinch.
+    /// Passes the scale since the pinch began.
+    fn pinch<A: 'static, F: Fn(&mut Context, f64, GestureState) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Gesture<Self, PinchRecognizer<F>> {
+        Gesture::new(self, PinchRecognizer::new(f))
+    }
+
+    /// Calls a function in response to a two-finger or trackpad rotation.
+    /// Passes the angle since the rotation began, in radians, positive
+    /// counter-clockwise.
+    fn rotate<A: 'static, F: Fn(&mut Context, f64, GestureState) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> Gesture<Self, RotateRecognizer<F>> {
+        Gesture::new(self, RotateRecognizer::new(f))
+    }
+
+    /// Runs a future when the view first appears and passes its result
+    /// to `f` on the main thread. The future is cancelled if the view goes away.
+    fn task<
+        Fut: Future + Send + 'static,
+        M: Fn() -> Fut + Clone + 'static,
+        F: Fn(&mut Context, Fut::Output) + Clone + 'static,
+    >(
+        self,
+        make_future: M,
+        f: F,
+    ) -> TaskView<Self, M, F>
+    where
+        Fut::Output: Send + 'static,
+    {
+        TaskView::new(self, make_future, f)
     }
 
     /// Calls a function in response to a touch.
@@ -170,6 +274,16 @@ pub trait Modifiers: View + Sized {
         Touch::new(self, TouchFunc { f })
     }
 
+    /// Calls a function when the user asks to close the window, instead of
+    /// closing it. Call `Context::quit` from the function to close it after all,
+    /// for example once unsaved changes are dealt with.
+    fn close_request<A: 'static, F: Fn(&mut Context) -> A + Clone + 'static>(
+        self,
+        f: F,
+    ) -> CloseRequestView<Self, F> {
+        CloseRequestView::new(self, f)
+    }
+
     /// Specify the title of the window.
     fn window_title(self, title: &str) -> TitleView<Self> {
         TitleView::new(self, title)
@@ -0,0 +1,157 @@
+use crate::*;
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use vello::util::RenderContext;
+use vello::{AaConfig, AaSupport, Renderer, RendererOptions, wgpu};
+
+pub use image::RgbaImage;
+
+/// Renders views to images without a window.
+///
+/// This uses vello's CPU shaders, so only a wgpu device is needed (a software
+/// adapter such as llvmpipe or WARP is fine). Keep one around when rendering
+/// many images, since creating the renderer is slow.
+pub struct OffscreenRenderer {
+    render_cx: RenderContext,
+    device_id: usize,
+    renderer: Renderer,
+
+    /// Color the image is cleared to before drawing.
+    pub base_color: Color,
+}
+
+impl OffscreenRenderer {
+    pub fn new() -> anyhow::Result<Self> {
+        let mut render_cx = RenderContext::new();
+        let device_id = pollster::block_on(render_cx.device(None))
+            .ok_or_else(|| anyhow::anyhow!("no compatible wgpu device found"))?;
+        let renderer = Renderer::new(
+            &render_cx.devices[device_id].device,
+            RendererOptions {
+                use_cpu: true,
+                antialiasing_support: AaSupport::area_only(),
+                num_init_threads: NonZeroUsize::new(1),
+            },
+        )
+        .map_err(|e| anyhow::format_err!("{e}"))?;
+
+        Ok(Self {
+            render_cx,
+            device_id,
+            renderer,
+            base_color: BLACK,
+        })
+    }
+
+    /// Lays out `view` at `size` (in points) and renders it at `scale` pixels per point.
+    pub fn render(
+        &mut self,
+        view: &impl View,
+        size: Size,
+        scale: f64,
+    ) -> anyhow::Result<RgbaImage> {
+        let mut cx = Context::new();
+        let scene = cx.render(view, size, scale);
+        self.render_scene(&scene, (size * scale).ceil())
+    }
+
+    /// Rasterizes a scene into an image of `size` pixels.
+    pub fn render_scene(&mut self, scene: &Scene, size: Size) -> anyhow::Result<RgbaImage> {
+        let width = (size.width as u32).max(1);
+        let height = (size.height as u32).max(1);
+        let device_handle = &self.render_cx.devices[self.device_id];
+        let device = &device_handle.device;
+        let queue = &device_handle.queue;
+
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen target"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.renderer
+            .render_to_texture(
+                device,
+                queue,
+                scene,
+                &target_view,
+                &vello::RenderParams {
+                    base_color: self.base_color,
+                    width,
+                    height,
+                    antialiasing_method: AaConfig::Area,
+                },
+            )
+            .map_err(|e| anyhow::format_err!("{e}"))?;
+
+        // Rows copied out of a texture must be aligned to 256 bytes.
+        let padded_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Offscreen readback"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Offscreen copy"),
+        });
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv()??;
+
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for row in 0..height {
+            let start = (row * padded_row) as usize;
+            pixels.extend_from_slice(&data[start..start + (width * 4) as usize]);
+        }
+        drop(data);
+        buffer.unmap();
+
+        RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow::anyhow!("image buffer has the wrong size"))
+    }
+}
+
+/// Renders a view to an image without opening a window.
+///
+/// `size` is in points and the image is `size * scale` pixels.
+pub fn render_to_image(view: &impl View, size: Size, scale: f64) -> anyhow::Result<RgbaImage> {
+    OffscreenRenderer::new()?.render(view, size, scale)
+}
+
+/// Writes an image to a PNG file.
+pub fn save_png(image: &RgbaImage, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    image.save_with_format(path, image::ImageFormat::Png)?;
+    Ok(())
+}
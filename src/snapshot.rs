@@ -0,0 +1,252 @@
+use crate::*;
+use std::cell::RefCell;
+use std::path::Path;
+
+/// Settings for `assert_snapshot!`.
+#[derive(Clone, Debug)]
+pub struct SnapshotOptions {
+    /// Size the view is laid out at, in points.
+    pub size: Size,
+
+    /// Pixels per point.
+    pub scale: f64,
+
+    /// Per-pixel perceptual difference (0 to 1) below which pixels are considered equal.
+    pub threshold: f64,
+
+    /// Fraction of pixels which may differ before the snapshot fails.
+    pub max_mismatch: f64,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            size: Size::new(200.0, 100.0),
+            scale: 1.0,
+            threshold: 0.1,
+            max_mismatch: 0.001,
+        }
+    }
+}
+
+/// Result of `compare_images`.
+pub struct ImageDiff {
+    /// Number of pixels which differ by more than the threshold.
+    pub mismatched: usize,
+
+    /// Mismatched pixels in red over a faded copy of the expected image.
+    pub image: RgbaImage,
+}
+
+/// Maximum value of `color_delta`.
+const MAX_DELTA: f64 = 35215.0;
+
+/// Blends a pixel with white according to its alpha.
+fn blend(c: &image::Rgba<u8>) -> [f64; 3] {
+    let a = c[3] as f64 / 255.0;
+    [0, 1, 2].map(|i| 255.0 + (c[i] as f64 - 255.0) * a)
+}
+
+/// Squared perceptual distance between two pixels in YIQ space,
+/// following the metric used by pixelmatch.
+fn color_delta(a: &image::Rgba<u8>, b: &image::Rgba<u8>) -> f64 {
+    let [r1, g1, b1] = blend(a);
+    let [r2, g2, b2] = blend(b);
+
+    let y = (r1 - r2) * 0.29889531 + (g1 - g2) * 0.58662247 + (b1 - b2) * 0.11448223;
+    let i = (r1 - r2) * 0.59597799 - (g1 - g2) * 0.2741761 - (b1 - b2) * 0.32180189;
+    let q = (r1 - r2) * 0.21147017 - (g1 - g2) * 0.52261711 + (b1 - b2) * 0.31114694;
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+/// Compares two images of the same size.
+///
+/// Returns `None` if the sizes differ.
+pub fn compare_images(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    threshold: f64,
+) -> Option<ImageDiff> {
+    if expected.dimensions() != actual.dimensions() {
+        return None;
+    }
+
+    let max_delta = MAX_DELTA * threshold * threshold;
+    let mut mismatched = 0;
+    let mut image = RgbaImage::new(expected.width(), expected.height());
+
+    for (x, y, e) in expected.enumerate_pixels() {
+        let a = actual.get_pixel(x, y);
+        let pixel = if color_delta(e, a) > max_delta {
+            mismatched += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b] = blend(e);
+            let gray = (0.299 * r + 0.587 * g + 0.114 * b) as u8;
+            let faded = 255 - (255 - gray) / 10;
+            image::Rgba([faded, faded, faded, 255])
+        };
+        image.put_pixel(x, y, pixel);
+    }
+
+    Some(ImageDiff { mismatched, image })
+}
+
+thread_local! {
+    static RENDERER: RefCell<Option<OffscreenRenderer>> = const { RefCell::new(None) };
+}
+
+/// Renders `view` and compares it against `dir/name.png`.
+///
+/// If `VUI_UPDATE_SNAPSHOTS` is set, the rendering is written as the new
+/// reference instead. A missing reference is a failure. On failure the
+/// rendering is written to `name.new.png` and a diff to `name.diff.png`.
+///
+/// Usually called through `assert_snapshot!`.
+pub fn check_snapshot(
+    view: &impl View,
+    name: &str,
+    dir: &Path,
+    options: &SnapshotOptions,
+) -> Result<(), String> {
+    let actual = RENDERER.with(|renderer| {
+        let mut renderer = renderer.borrow_mut();
+        if renderer.is_none() {
+            *renderer = Some(OffscreenRenderer::new().map_err(|e| e.to_string())?);
+        }
+        renderer
+            .as_mut()
+            .unwrap()
+            .render(view, options.size, options.scale)
+            .map_err(|e| e.to_string())
+    })?;
+
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let reference_path = dir.join(format!("{name}.png"));
+    let new_path = dir.join(format!("{name}.new.png"));
+    let diff_path = dir.join(format!("{name}.diff.png"));
+
+    if std::env::var_os("VUI_UPDATE_SNAPSHOTS").is_some() {
+        let _ = std::fs::remove_file(&new_path);
+        let _ = std::fs::remove_file(&diff_path);
+        return save_png(&actual, &reference_path).map_err(|e| e.to_string());
+    }
+
+    if !reference_path.exists() {
+        save_png(&actual, &new_path).map_err(|e| e.to_string())?;
+        return Err(format!(
+            "snapshot {name} has no reference at {}; new rendering at {}. \
+             Set VUI_UPDATE_SNAPSHOTS=1 to record it",
+            reference_path.display(),
+            new_path.display()
+        ));
+    }
+
+    let expected = image::open(&reference_path)
+        .map_err(|e| format!("failed to read {}: {e}", reference_path.display()))?
+        .to_rgba8();
+
+    let failure = match compare_images(&expected, &actual, options.threshold) {
+        None => Some(format!(
+            "snapshot {name} is {:?} but the reference is {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        )),
+        Some(diff) => {
+            let allowed =
+                (options.max_mismatch * (actual.width() * actual.height()) as f64) as usize;
+            if diff.mismatched > allowed {
+                save_png(&diff.image, &diff_path).map_err(|e| e.to_string())?;
+                Some(format!(
+                    "snapshot {name} differs in {} pixels (allowed {allowed}), see {}",
+                    diff.mismatched,
+                    diff_path.display()
+                ))
+            } else {
+                None
+            }
+        }
+    };
+
+    match failure {
+        Some(message) => {
+            save_png(&actual, &new_path).map_err(|e| e.to_string())?;
+            Err(format!(
+                "{message}; new rendering at {}",
+                new_path.display()
+            ))
+        }
+        None => {
+            let _ = std::fs::remove_file(&new_path);
+            let _ = std::fs::remove_file(&diff_path);
+            Ok(())
+        }
+    }
+}
+
+/// Renders a view and compares it against a stored PNG in the calling
+/// crate's `tests/snapshots` directory, panicking if it differs.
+///
+/// ```no_run
+/// # use vui::*;
+/// assert_snapshot!(button("ok", |_| ()), "ok_button");
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($view:expr, $name:expr) => {
+        $crate::assert_snapshot!($view, $name, $crate::SnapshotOptions::default())
+    };
+    ($view:expr, $name:expr, $options:expr) => {
+        if let Err(message) = $crate::check_snapshot(
+            &$view,
+            $name,
+            ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots")),
+            &$options,
+        ) {
+            panic!("{}", message);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn solid(color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(10, 10, image::Rgba(color))
+    }
+
+    #[test]
+    fn test_compare_identical() {
+        let a = solid([10, 20, 30, 255]);
+        let diff = compare_images(&a, &a.clone(), 0.1).unwrap();
+        assert_eq!(diff.mismatched, 0);
+    }
+
+    #[test]
+    fn test_compare_within_tolerance() {
+        let a = solid([100, 100, 100, 255]);
+        let b = solid([102, 101, 100, 255]);
+        let diff = compare_images(&a, &b, 0.1).unwrap();
+        assert_eq!(diff.mismatched, 0);
+    }
+
+    #[test]
+    fn test_compare_mismatch() {
+        let a = solid([0, 0, 0, 255]);
+        let mut b = a.clone();
+        b.put_pixel(3, 4, image::Rgba([255, 255, 255, 255]));
+        let diff = compare_images(&a, &b, 0.1).unwrap();
+        assert_eq!(diff.mismatched, 1);
+        assert_eq!(*diff.image.get_pixel(3, 4), image::Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_compare_size_mismatch() {
+        let a = solid([0, 0, 0, 255]);
+        let b = RgbaImage::new(5, 5);
+        assert!(compare_images(&a, &b, 0.1).is_none());
+    }
+}
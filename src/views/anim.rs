@@ -30,7 +30,10 @@ where
         actions: &mut Vec<Box<dyn Any>>,
     ) {
         if let Event::Anim = event {
-            (self.func)(ctx, 1.0 / 60.0) // XXX: assume 60fps for now.
+            let dt = ctx.delta_time();
+            (self.func)(ctx, dt);
+            // The callback runs every frame, so keep them coming.
+            ctx.request_frame();
         }
 
         path.push(0);
@@ -72,3 +75,37 @@ where
         path.pop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_anim_measured_dt() {
+        let ui = state(|| 0.0, |t, _| rectangle().anim(move |cx, dt| cx[t] += dt));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let t = harness.state_handle::<f64>(&vec![0]);
+        let clock = ManualClock::default();
+        harness.cx_mut().set_clock(clock.clone());
+
+        // There's nothing to measure the first frame from.
+        harness.cx_mut()[t] = 0.0;
+        harness.update();
+        assert_eq!(harness.cx()[t], 1.0 / 60.0);
+
+        harness.cx_mut()[t] = 0.0;
+        clock.advance(0.1);
+        harness.update();
+        assert_eq!(harness.cx()[t], 0.1);
+        assert_eq!(harness.cx().now(), 0.1);
+
+        // Stopped clocks and long pauses don't upset animations.
+        harness.cx_mut()[t] = 0.0;
+        harness.update();
+        assert_eq!(harness.cx()[t], 0.0);
+        clock.advance(10.0);
+        harness.update();
+        assert_eq!(harness.cx()[t], 0.25);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::*;
+use std::any::Any;
+use std::marker::PhantomData;
+
+/// The value an `animation` modifier watches, and the frame it last changed on.
+struct AnimationTrigger<T> {
+    value: T,
+    changed: Option<u64>,
+}
+
+/// Struct for the `animation` modifier.
+#[derive(Clone)]
+pub struct AnimationView<V, B, T> {
+    child: V,
+    animation: Animation,
+    value: B,
+    phantom: PhantomData<T>,
+}
+
+impl<V, B, T> AnimationView<V, B, T>
+where
+    V: View,
+    B: Binding<T>,
+    T: Clone + PartialEq + 'static,
+{
+    pub fn new(child: V, animation: Animation, value: B) -> Self {
+        Self {
+            child,
+            animation,
+            value,
+            phantom: Default::default(),
+        }
+    }
+
+    /// Sets the animation for the views inside while the value has just changed.
+    /// Returns what to restore afterwards.
+    fn begin(&self, path: &IdPath, ctx: &mut Context) -> Option<Option<AnimationScope>> {
+        let id = ctx.view_id(path);
+        let value = self.value.get(ctx).clone();
+        let initial = value.clone();
+        ctx.init_state(id, &move || AnimationTrigger {
+            value: initial.clone(),
+            changed: None,
+        });
+
+        let frame = ctx.animator.frame;
+        let trigger = ctx.state_mut::<AnimationTrigger<T>>(id);
+        if trigger.value != value {
+            trigger.value = value;
+            trigger.changed = Some(frame);
+        }
+        let changed = trigger.changed;
+
+        ctx.animator
+            .scope_in_effect(changed)
+            .then(|| ctx.set_env(&AnimationScope(self.animation)))
+    }
+
+    fn end(ctx: &mut Context, old: Option<Option<AnimationScope>>) {
+        if let Some(old) = old {
+            ctx.restore_env(old);
+        }
+    }
+}
+
+impl<V, B, T> DynView for AnimationView<V, B, T>
+where
+    V: View,
+    B: Binding<T>,
+    T: Clone + PartialEq + 'static,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        let old = self.begin(path, ctx);
+        path.push(0);
+        self.child.process(event, path, ctx, actions);
+        path.pop();
+        Self::end(ctx, old);
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        let old = self.begin(path, ctx);
+        path.push(0);
+        let scene = self.child.draw(path, ctx);
+        path.pop();
+        Self::end(ctx, old);
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        let old = self.begin(path, args.ctx);
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        Self::end(args.ctx, old);
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        let old = self.begin(path, ctx);
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        Self::end(ctx, old);
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
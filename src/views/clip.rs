@@ -3,6 +3,15 @@ use kurbo::Affine;
 use peniko::Mix;
 use std::any::Any;
 
+/// Returns `child`, transformed by `transform` and then clipped to `rect`.
+pub(crate) fn clip_scene(rect: Rect, child: &Scene, transform: Affine) -> Scene {
+    let mut scene = Scene::new();
+    scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rect);
+    scene.append(child, Some(transform));
+    scene.pop_layer();
+    scene
+}
+
 #[derive(Clone)]
 pub struct Clip<V> {
     child: V,
@@ -39,12 +48,9 @@ where
 
     fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
         let rect = self.geom(path, ctx);
-        let mut scene = Scene::new();
-        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rect);
         path.push(0);
-        scene.append(&self.child.draw(path, ctx), None);
+        let scene = clip_scene(rect, &self.child.draw(path, ctx), Affine::IDENTITY);
         path.pop();
-        scene.pop_layer();
         scene
     }
 
@@ -0,0 +1,77 @@
+use crate::*;
+use std::any::Any;
+
+/// Struct for the `close_request` modifier.
+#[derive(Clone)]
+pub struct CloseRequestView<V, F> {
+    child: V,
+    func: F,
+}
+
+impl<V, F, A> CloseRequestView<V, F>
+where
+    V: View,
+    F: Fn(&mut Context) -> A + Clone + 'static,
+{
+    pub fn new(v: V, f: F) -> Self {
+        CloseRequestView { child: v, func: f }
+    }
+}
+
+impl<V, F, A> DynView for CloseRequestView<V, F>
+where
+    V: View,
+    F: Fn(&mut Context) -> A + Clone + 'static,
+    A: 'static,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        path.push(0);
+        self.child.process(event, path, ctx, actions);
+        path.pop();
+
+        if let Event::CloseRequested = event {
+            // Keep the window open. The handler calls `Context::quit` to close it.
+            ctx.event_handled = true;
+            actions.push(Box::new((self.func)(ctx)));
+        }
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        path.push(0);
+        let scene = self.child.draw(path, ctx);
+        path.pop();
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
@@ -325,7 +325,7 @@ impl DynCommandBase for NullCommand {
         self.name.clone()
     }
     fn key(&self) -> Option<HotKey> {
-        None
+        self.key
     }
 }
 
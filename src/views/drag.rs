@@ -17,6 +17,7 @@ pub trait DragFn: Clone {
         delta: Vec2,
         state: GestureState,
         button: Option<MouseButton>,
+        touch_id: usize,
         actions: &mut Vec<Box<dyn Any>>,
     );
 }
@@ -36,6 +37,7 @@ impl<A: 'static, F: Fn(&mut Context, Vec2, GestureState, Option<MouseButton>) ->
         delta: Vec2,
         state: GestureState,
         button: Option<MouseButton>,
+        _touch_id: usize,
         actions: &mut Vec<Box<dyn Any>>,
     ) {
         actions.push(Box::new((self.f)(ctx, delta, state, button)))
@@ -57,12 +59,35 @@ impl<A: 'static, F: Fn(&mut Context, Point, GestureState, Option<MouseButton>) -
         _delta: Vec2,
         state: GestureState,
         button: Option<MouseButton>,
+        _touch_id: usize,
         actions: &mut Vec<Box<dyn Any>>,
     ) {
         actions.push(Box::new((self.f)(ctx, pt, state, button)))
     }
 }
 
+#[derive(Clone)]
+pub struct DragFuncT<F> {
+    pub f: F,
+}
+
+impl<A: 'static, F: Fn(&mut Context, usize, Vec2, GestureState) -> A + Clone> DragFn
+    for DragFuncT<F>
+{
+    fn call(
+        &self,
+        ctx: &mut Context,
+        _pt: Point,
+        delta: Vec2,
+        state: GestureState,
+        _button: Option<MouseButton>,
+        touch_id: usize,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        actions.push(Box::new((self.f)(ctx, touch_id, delta, state)))
+    }
+}
+
 #[derive(Clone)]
 pub struct DragFuncS<F, B, T> {
     pub f: F,
@@ -84,6 +109,7 @@ impl<
         delta: Vec2,
         state: GestureState,
         button: Option<MouseButton>,
+        _touch_id: usize,
         actions: &mut Vec<Box<dyn Any>>,
     ) {
         actions.push(Box::new((self.f)(
@@ -95,7 +121,10 @@ impl<
     }
 }
 
-/// Struct for the `drag` and `drag_p` gestures.
+/// Struct for the `drag`, `drag_p`, `drag_s` and `drag_t` gestures.
+///
+/// Each touch is tracked separately, so several fingers can drag
+/// different views (or the same view) at once.
 #[derive(Clone)]
 pub struct Drag<V, F> {
     child: V,
@@ -140,11 +169,12 @@ where
         let vid = ctx.view_id(path);
         match &event {
             Event::TouchBegin { id, position } => {
-                if ctx.touches[*id].is_default() && self.hittest(path, *position, ctx).is_some() {
-                    ctx.touches[*id] = vid;
+                if self.hittest(path, *position, ctx).is_some() && ctx.capture_touch(*id, vid) {
                     ctx.starts[*id] = *position;
                     ctx.previous_position[*id] = *position;
-                    ctx.grab_cursor = self.grab;
+                    if *id == 0 {
+                        ctx.grab_cursor = self.grab;
+                    }
 
                     self.func.call(
                         ctx,
@@ -152,6 +182,7 @@ where
                         Vec2::ZERO,
                         GestureState::Began,
                         ctx.mouse_button,
+                        *id,
                         actions,
                     );
                 }
@@ -161,22 +192,24 @@ where
                 position,
                 delta,
             } => {
-                if ctx.touches[*id] == vid {
+                if ctx.has_touch(*id, vid) {
                     self.func.call(
                         ctx,
                         *position,
                         *delta,
                         GestureState::Changed,
                         ctx.mouse_button,
+                        *id,
                         actions,
                     );
                     ctx.previous_position[*id] = *position;
                 }
             }
             Event::TouchEnd { id, position } => {
-                if ctx.touches[*id] == vid {
-                    ctx.touches[*id] = ViewId::default();
-                    ctx.grab_cursor = false;
+                if ctx.release_touch(*id, vid) {
+                    if *id == 0 {
+                        ctx.grab_cursor = false;
+                    }
 
                     self.func.call(
                         ctx,
@@ -184,10 +217,29 @@ where
                         Vec2::ZERO,
                         GestureState::Ended,
                         ctx.mouse_button,
+                        *id,
                         actions,
                     );
                 }
             }
+            Event::PointerMove { position, delta } => {
+                // The mouse is touch 0.
+                if ctx.has_touch(0, vid) {
+                    self.func.call(
+                        ctx,
+                        *position,
+                        *delta,
+                        GestureState::Changed,
+                        ctx.mouse_button,
+                        0,
+                        actions,
+                    );
+                    ctx.previous_position[0] = *position;
+                }
+                path.push(0);
+                self.child.process(event, path, ctx, actions);
+                path.pop();
+            }
             _ => {
                 path.push(0);
                 self.child.process(event, path, ctx, actions);
@@ -294,4 +346,77 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_drag_pointer_move() {
+        let ui = state(
+            || Vec2::ZERO,
+            |offset, _| {
+                rectangle().drag(move |ctx, delta, _, _| {
+                    ctx[offset] += delta;
+                })
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<Vec2>(&vec![0]);
+
+        // Moving without a button down doesn't drag.
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_move((20.0, 10.0));
+        assert_eq!(harness.cx()[s], Vec2::ZERO);
+
+        harness.drag((50.0, 50.0), (70.0, 40.0), 4);
+        assert_eq!(harness.cx()[s], Vec2::new(20.0, -10.0));
+    }
+
+    #[test]
+    fn test_drag_two_fingers() {
+        let ui = state(
+            || [0.0, 0.0],
+            |values, _| {
+                hstack((
+                    rectangle().drag(move |ctx, delta, _, _| ctx[values][0] += delta.x),
+                    rectangle().drag(move |ctx, delta, _, _| ctx[values][1] += delta.x),
+                ))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (200.0, 100.0));
+        let s = harness.state_handle::<[f64; 2]>(&vec![0]);
+
+        harness.touch_begin(1, (50.0, 50.0));
+        harness.touch_begin(2, (150.0, 50.0));
+        harness.touch_move(1, (60.0, 50.0));
+        harness.touch_move(2, (130.0, 50.0));
+        harness.touch_move(1, (65.0, 50.0));
+        assert_eq!(harness.cx()[s], [15.0, -20.0]);
+
+        // Lifting one finger leaves the other dragging.
+        harness.touch_end(1, (65.0, 50.0));
+        harness.touch_move(2, (140.0, 50.0));
+        harness.touch_end(2, (140.0, 50.0));
+        assert_eq!(harness.cx()[s], [15.0, -10.0]);
+    }
+
+    #[test]
+    fn test_drag_touch_id() {
+        let ui = state(
+            || vec![],
+            |ids, _| {
+                rectangle().drag_t(move |ctx, id, _, state| {
+                    if state == GestureState::Began {
+                        ctx[ids].push(id)
+                    }
+                })
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<Vec<usize>>(&vec![0]);
+
+        harness.touch_begin(3, (20.0, 20.0));
+        harness.touch_begin(1, (80.0, 80.0));
+        harness.tap_at((50.0, 50.0));
+
+        // The mouse is touch 0.
+        assert_eq!(harness.cx()[s], vec![3, 1, 0]);
+    }
 }
@@ -105,7 +105,7 @@ where
         path.push(0);
         self.child.process(event, path, ctx, actions);
         path.pop();
-        old.and_then(|s| ctx.set_env(&s));
+        ctx.restore_env(old);
     }
 
     fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
@@ -113,7 +113,7 @@ where
         path.push(0);
         let scene = self.child.draw(path, ctx);
         path.pop();
-        old.and_then(|s| ctx.set_env(&s));
+        ctx.restore_env(old);
         scene
     }
 
@@ -122,7 +122,7 @@ where
         path.push(0);
         let size = self.child.layout(path, args);
         path.pop();
-        old.and_then(|s| args.ctx.set_env(&s));
+        args.ctx.restore_env(old);
         size
     }
 
@@ -131,7 +131,7 @@ where
         path.push(0);
         let r = self.child.hittest(path, pt, ctx);
         path.pop();
-        old.and_then(|s| ctx.set_env(&s));
+        ctx.restore_env(old);
         r
     }
 
@@ -140,7 +140,7 @@ where
         path.push(0);
         self.child.commands(path, ctx, cmds);
         path.pop();
-        old.and_then(|s| ctx.set_env(&s));
+        ctx.restore_env(old);
     }
 
     fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
@@ -148,6 +148,6 @@ where
         path.push(0);
         self.child.gc(path, ctx, map);
         path.pop();
-        old.and_then(|s| ctx.set_env(&s));
+        ctx.restore_env(old);
     }
 }
@@ -0,0 +1,513 @@
+use crate::*;
+use std::any::Any;
+use std::collections::HashMap;
+
+/// What a recognizer makes of the touches it has seen so far.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Recognition {
+    /// Could still turn out to be the gesture.
+    Possible,
+
+    /// The gesture has happened, but it yields to other recognizers in
+    /// the arena which could still recognize theirs. If all the others
+    /// end up ready too, the last one to become ready wins. Taps use
+    /// this so a double tap beats a single tap.
+    Ready,
+
+    /// The gesture has happened. Competing recognizers are rejected.
+    Accept,
+
+    /// Not the gesture.
+    Reject,
+}
+
+/// A gesture recognizer, which is attached to a view with `Gesture`.
+///
+/// Recognizers under a touch compete in an arena, so only one of them
+/// recognizes its gesture. A recognizer following a touch brings its
+/// arena along to the next touch, which is how multi-taps and
+/// two-finger gestures compete with single-touch ones.
+pub trait Recognizer: Clone + 'static {
+    /// Kept in the `Context` between events.
+    type State: Default + 'static;
+
+    /// A touch has begun inside the view. Returns `None` to ignore it,
+    /// and otherwise follows it unless the result is `Reject`.
+    /// `now` is the clock time in seconds, from `Context::now`.
+    fn begin(
+        &self,
+        state: &mut Self::State,
+        id: usize,
+        position: Point,
+        button: Option<MouseButton>,
+        now: f64,
+    ) -> Option<Recognition>;
+
+    /// Called with moves and ends of followed touches, and with
+    /// `Event::Anim` while following touches or waiting in an arena.
+    /// Recognizers time touches against `now`, as in `begin`.
+    fn update(&self, state: &mut Self::State, event: &Event, now: f64) -> Recognition;
+
+    /// Called once the recognizer wins its arena.
+    fn recognized(&self, ctx: &mut Context, state: &Self::State, actions: &mut Vec<Box<dyn Any>>);
+
+    /// Called for each move after winning, for continuous gestures.
+    fn changed(&self, _ctx: &mut Context, _state: &Self::State, _actions: &mut Vec<Box<dyn Any>>) {}
+
+    /// Called when the touches end (or `update` rejects) after winning.
+    fn ended(&self, _ctx: &mut Context, _state: &Self::State, _actions: &mut Vec<Box<dyn Any>>) {}
+
+    /// Trackpad `Pinch` and `Rotate` events over the view, which don't go
+    /// through the arena. Returns whether the event was used.
+    fn trackpad(
+        &self,
+        _ctx: &mut Context,
+        _state: &mut Self::State,
+        _event: &Event,
+        _actions: &mut Vec<Box<dyn Any>>,
+    ) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Member {
+    Possible,
+    /// Ready, with the order in which it became ready.
+    Ready(u64),
+    Won,
+    Lost,
+}
+
+#[derive(Default)]
+struct Arena {
+    members: Vec<(ViewId, Member)>,
+}
+
+impl Arena {
+    /// Picks a winner if one is known.
+    fn resolve(&mut self) {
+        let winner = if let Some((id, _)) = self.members.iter().find(|(_, m)| *m == Member::Won) {
+            *id
+        } else if self.members.iter().all(|(_, m)| *m != Member::Possible) {
+            let last_ready = self.members.iter().filter_map(|(id, m)| match m {
+                Member::Ready(order) => Some((*order, *id)),
+                _ => None,
+            });
+            match last_ready.max_by_key(|(order, _)| *order) {
+                Some((_, id)) => id,
+                None => return,
+            }
+        } else {
+            return;
+        };
+
+        for (id, member) in &mut self.members {
+            *member = if *id == winner {
+                Member::Won
+            } else {
+                Member::Lost
+            };
+        }
+    }
+}
+
+/// Arenas in which recognizers compete for touches.
+#[derive(Default)]
+pub(crate) struct GestureArenas {
+    arenas: HashMap<u64, Arena>,
+    next_arena: u64,
+
+    /// Arena joined by the recognizers under each touch as it began.
+    touch_arenas: [Option<u64>; 16],
+
+    /// Orders recognizers by when they became ready.
+    next_ready: u64,
+}
+
+impl GestureArenas {
+    fn arena_of(&self, vid: ViewId) -> Option<u64> {
+        self.arenas
+            .iter()
+            .find(|(_, arena)| arena.members.iter().any(|(id, _)| *id == vid))
+            .map(|(key, _)| *key)
+    }
+
+    /// Whether a recognizer is still competing or waiting for its result.
+    pub(crate) fn contains(&self, vid: ViewId) -> bool {
+        self.arena_of(vid).is_some()
+    }
+
+    /// Called before a `TouchBegin` is processed.
+    pub(crate) fn touch_began(&mut self, id: usize) {
+        self.touch_arenas[id] = None;
+    }
+
+    /// Enters a recognizer into the arena for touch `id`. A recognizer
+    /// already in an arena merges it with the touch's.
+    pub(crate) fn join(&mut self, vid: ViewId, id: usize) {
+        let touch_arena = self.touch_arenas[id].filter(|key| self.arenas.contains_key(key));
+        match (self.arena_of(vid), touch_arena) {
+            (Some(key), Some(other)) if key != other => {
+                let merged = self.arenas.remove(&other).unwrap();
+                let arena = self.arenas.get_mut(&key).unwrap();
+                arena.members.extend(merged.members);
+                arena.resolve();
+                for touch_arena in &mut self.touch_arenas {
+                    if *touch_arena == Some(other) {
+                        *touch_arena = Some(key);
+                    }
+                }
+            }
+            (Some(key), None) => self.touch_arenas[id] = Some(key),
+            (None, Some(key)) => {
+                let arena = self.arenas.get_mut(&key).unwrap();
+                arena.members.push((vid, Member::Possible));
+                arena.resolve();
+            }
+            (None, None) => {
+                let key = self.next_arena;
+                self.next_arena += 1;
+                self.arenas.insert(
+                    key,
+                    Arena {
+                        members: vec![(vid, Member::Possible)],
+                    },
+                );
+                self.touch_arenas[id] = Some(key);
+            }
+            _ => (),
+        }
+    }
+
+    /// Rejects the recognizers still competing for touch `id`, for when an
+    /// outer view such as a scroll view has taken the touch over.
+    pub(crate) fn cancel_touch(&mut self, id: usize) {
+        let Some(key) = self.touch_arenas[id].take() else {
+            return;
+        };
+        if let Some(arena) = self.arenas.get_mut(&key) {
+            for (_, member) in &mut arena.members {
+                if matches!(member, Member::Possible | Member::Ready(_)) {
+                    *member = Member::Lost;
+                }
+            }
+        }
+    }
+
+    /// Records a recognizer's decision and settles its arena if possible.
+    pub(crate) fn report(&mut self, vid: ViewId, recognition: Recognition) {
+        let Some(key) = self.arena_of(vid) else {
+            return;
+        };
+        let arena = self.arenas.get_mut(&key).unwrap();
+        let member = &mut arena
+            .members
+            .iter_mut()
+            .find(|(id, _)| *id == vid)
+            .unwrap()
+            .1;
+        match (recognition, *member) {
+            (Recognition::Accept, Member::Possible | Member::Ready(_)) => *member = Member::Won,
+            (Recognition::Ready, Member::Possible) => {
+                *member = Member::Ready(self.next_ready);
+                self.next_ready += 1;
+            }
+            (Recognition::Reject, Member::Possible | Member::Ready(_)) => *member = Member::Lost,
+            _ => (),
+        }
+        arena.resolve();
+    }
+
+    /// Collects a recognizer's result, once the arena has settled:
+    /// `Some(true)` if it won and `Some(false)` if it lost.
+    pub(crate) fn outcome(&mut self, vid: ViewId) -> Option<bool> {
+        let key = self.arena_of(vid)?;
+        let arena = self.arenas.get_mut(&key).unwrap();
+        let index = arena.members.iter().position(|(id, _)| *id == vid)?;
+        let won = match arena.members[index].1 {
+            Member::Won => true,
+            Member::Lost => false,
+            _ => return None,
+        };
+        arena.members.remove(index);
+        if arena.members.is_empty() {
+            self.arenas.remove(&key);
+        }
+        Some(won)
+    }
+
+    /// Drops recognizers whose views have gone away.
+    pub(crate) fn retain(&mut self, keep: impl Fn(&ViewId) -> bool) {
+        for arena in self.arenas.values_mut() {
+            arena.members.retain(|(id, _)| keep(id));
+            arena.resolve();
+        }
+        self.arenas.retain(|_, arena| !arena.members.is_empty());
+    }
+}
+
+/// Per-view state of a `Gesture`.
+#[derive(Default)]
+struct GestureTracker<S> {
+    /// Touches the recognizer is following.
+    touches: Vec<usize>,
+
+    /// Whether the recognizer has won its arena.
+    won: bool,
+
+    state: S,
+}
+
+/// Struct for gestures recognized by a `Recognizer`, such as
+/// `tap`, `double_tap`, `long_press`, `swipe`, `pinch` and `rotate`.
+#[derive(Clone)]
+pub struct Gesture<V, R> {
+    child: V,
+    recognizer: R,
+}
+
+impl<V, R> Gesture<V, R>
+where
+    V: View,
+    R: Recognizer,
+{
+    pub fn new(child: V, recognizer: R) -> Self {
+        Self { child, recognizer }
+    }
+
+    /// Collects the arena's result, and resets once the gesture is over.
+    fn settle(
+        &self,
+        vid: ViewId,
+        t: &mut GestureTracker<R::State>,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        match ctx.gestures.outcome(vid) {
+            Some(true) => {
+                t.won = true;
+                self.recognizer.recognized(ctx, &t.state, actions);
+            }
+            Some(false) => *t = GestureTracker::default(),
+            None => (),
+        }
+
+        if t.touches.is_empty() {
+            if t.won {
+                self.recognizer.ended(ctx, &t.state, actions);
+                *t = GestureTracker::default();
+            } else if !ctx.gestures.contains(vid) {
+                *t = GestureTracker::default();
+            }
+        }
+    }
+}
+
+impl<V, R> DynView for Gesture<V, R>
+where
+    V: View,
+    R: Recognizer,
+{
+    fn process(
+        &self,
+        original_event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        let vid = ctx.view_id(path);
+        ctx.init_state(vid, &GestureTracker::<R::State>::default);
+        let mut t = std::mem::take(ctx.state_mut::<GestureTracker<R::State>>(vid));
+
+        // Another recognizer may have settled the arena since we last looked.
+        self.settle(vid, &mut t, ctx, actions);
+
+        // The mouse is touch 0.
+        let mouse_move;
+        let event = match original_event {
+            Event::PointerMove { position, delta } if t.touches.contains(&0) => {
+                mouse_move = Event::TouchMove {
+                    id: 0,
+                    position: *position,
+                    delta: *delta,
+                };
+                &mouse_move
+            }
+            _ => original_event,
+        };
+
+        let result = match event {
+            Event::TouchBegin { id, position } => {
+                if self.hittest(path, *position, ctx).is_some() {
+                    let recognition = self.recognizer.begin(
+                        &mut t.state,
+                        *id,
+                        *position,
+                        ctx.mouse_button,
+                        ctx.now(),
+                    );
+                    if recognition.is_some_and(|r| r != Recognition::Reject) {
+                        t.touches.push(*id);
+                        if !t.won {
+                            ctx.gestures.join(vid, *id);
+                        }
+                    }
+                    recognition
+                } else {
+                    None
+                }
+            }
+            Event::TouchMove { id, .. } | Event::TouchEnd { id, .. } if t.touches.contains(id) => {
+                let recognition = self.recognizer.update(&mut t.state, event, ctx.now());
+                if let Event::TouchEnd { .. } = event {
+                    t.touches.retain(|touch| touch != id);
+                }
+                Some(recognition)
+            }
+            Event::Anim if !t.touches.is_empty() || ctx.gestures.contains(vid) => {
+                // Recognizers time touches, such as for long presses.
+                ctx.request_frame();
+                Some(self.recognizer.update(&mut t.state, event, ctx.now()))
+            }
+            Event::Pinch { position, .. } | Event::Rotate { position, .. } => {
+                if !ctx.event_handled
+                    && self.hittest(path, *position, ctx).is_some()
+                    && self.recognizer.trackpad(ctx, &mut t.state, event, actions)
+                {
+                    ctx.event_handled = true;
+                }
+                None
+            }
+            _ => None,
+        };
+
+        if t.won {
+            match result {
+                Some(Recognition::Reject) => t.touches.clear(),
+                Some(_) if matches!(event, Event::TouchMove { .. }) => {
+                    self.recognizer.changed(ctx, &t.state, actions)
+                }
+                _ => (),
+            }
+        } else if let Some(recognition) = result {
+            ctx.gestures.report(vid, recognition);
+        }
+
+        self.settle(vid, &mut t, ctx, actions);
+
+        *ctx.state_mut::<GestureTracker<R::State>>(vid) = t;
+
+        path.push(0);
+        self.child.process(original_event, path, ctx, actions);
+        path.pop();
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        path.push(0);
+        let scene = self.child.draw(path, ctx);
+        path.pop();
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn ids(n: u64) -> Vec<ViewId> {
+        (1..=n).map(|id| ViewId { id }).collect()
+    }
+
+    #[test]
+    fn test_arena_accept_rejects_others() {
+        let mut arenas = GestureArenas::default();
+        let v = ids(2);
+        arenas.join(v[0], 0);
+        arenas.join(v[1], 0);
+
+        arenas.report(v[1], Recognition::Accept);
+        assert_eq!(arenas.outcome(v[0]), Some(false));
+        assert_eq!(arenas.outcome(v[1]), Some(true));
+        assert!(!arenas.contains(v[0]) && !arenas.contains(v[1]));
+    }
+
+    #[test]
+    fn test_arena_ready_waits() {
+        let mut arenas = GestureArenas::default();
+        let v = ids(3);
+        arenas.join(v[0], 0);
+        arenas.join(v[1], 0);
+        arenas.join(v[2], 0);
+
+        arenas.report(v[0], Recognition::Ready);
+        arenas.report(v[1], Recognition::Ready);
+        assert_eq!(arenas.outcome(v[0]), None);
+
+        // Once nothing else could win, the last to become ready does.
+        arenas.report(v[2], Recognition::Reject);
+        assert_eq!(arenas.outcome(v[0]), Some(false));
+        assert_eq!(arenas.outcome(v[1]), Some(true));
+        assert_eq!(arenas.outcome(v[2]), Some(false));
+    }
+
+    #[test]
+    fn test_arena_cancel_touch() {
+        let mut arenas = GestureArenas::default();
+        let v = ids(2);
+        arenas.join(v[0], 0);
+        arenas.join(v[1], 0);
+        arenas.report(v[0], Recognition::Ready);
+
+        arenas.cancel_touch(0);
+        assert_eq!(arenas.outcome(v[0]), Some(false));
+        assert_eq!(arenas.outcome(v[1]), Some(false));
+        assert!(!arenas.contains(v[0]) && !arenas.contains(v[1]));
+    }
+
+    #[test]
+    fn test_arena_follows_touches() {
+        let mut arenas = GestureArenas::default();
+        let v = ids(2);
+        arenas.join(v[0], 0);
+        arenas.join(v[1], 0);
+        arenas.report(v[0], Recognition::Ready);
+
+        // A new touch starts a new arena, unless a recognizer
+        // brings its arena along.
+        arenas.touch_began(0);
+        arenas.join(v[1], 0);
+        arenas.report(v[1], Recognition::Ready);
+
+        assert_eq!(arenas.outcome(v[0]), Some(false));
+        assert_eq!(arenas.outcome(v[1]), Some(true));
+    }
+}
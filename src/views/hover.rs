@@ -2,7 +2,15 @@ use crate::*;
 use std::any::Any;
 
 pub trait HoverFn: Clone {
-    fn call(&self, ctx: &mut Context, pt: Point, inside: bool, actions: &mut Vec<Box<dyn Any>>);
+    /// `changed` is true when the pointer has just entered or left the view.
+    fn call(
+        &self,
+        ctx: &mut Context,
+        pt: Point,
+        inside: bool,
+        changed: bool,
+        actions: &mut Vec<Box<dyn Any>>,
+    );
 }
 
 #[derive(Clone)]
@@ -11,7 +19,14 @@ pub struct HoverFuncP<F> {
 }
 
 impl<A: 'static, F: Fn(&mut Context, Point) -> A + Clone + 'static> HoverFn for HoverFuncP<F> {
-    fn call(&self, ctx: &mut Context, pt: Point, inside: bool, actions: &mut Vec<Box<dyn Any>>) {
+    fn call(
+        &self,
+        ctx: &mut Context,
+        pt: Point,
+        inside: bool,
+        _changed: bool,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
         if inside {
             actions.push(Box::new((self.f)(ctx, pt)))
         }
@@ -24,8 +39,17 @@ pub struct HoverFunc<F> {
 }
 
 impl<A: 'static, F: Fn(&mut Context, bool) -> A + Clone + 'static> HoverFn for HoverFunc<F> {
-    fn call(&self, ctx: &mut Context, _pt: Point, inside: bool, actions: &mut Vec<Box<dyn Any>>) {
-        actions.push(Box::new((self.f)(ctx, inside)))
+    fn call(
+        &self,
+        ctx: &mut Context,
+        _pt: Point,
+        inside: bool,
+        changed: bool,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        if changed {
+            actions.push(Box::new((self.f)(ctx, inside)))
+        }
     }
 }
 
@@ -44,6 +68,23 @@ where
     pub fn new(v: V, f: F) -> Self {
         Self { child: v, func: f }
     }
+
+    /// Records whether the pointer is inside and tells the callback.
+    fn update(
+        &self,
+        vid: ViewId,
+        pt: Point,
+        inside: bool,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        let changed = if inside {
+            ctx.hovered.insert(vid)
+        } else {
+            ctx.hovered.remove(&vid)
+        };
+        self.func.call(ctx, pt, inside, changed, actions);
+    }
 }
 
 impl<V, F> DynView for Hover<V, F>
@@ -58,15 +99,18 @@ where
         ctx: &mut Context,
         actions: &mut Vec<Box<dyn Any>>,
     ) {
-        if let Event::TouchMove { position, .. } = &event {
-            if ctx.mouse_button.is_none() {
+        let vid = ctx.view_id(path);
+        match &event {
+            Event::PointerMove { position, .. } | Event::TouchEnd { position, .. } => {
                 let inside = self.hittest(path, *position, ctx).is_some();
-                self.func.call(ctx, *position, inside, actions);
+                self.update(vid, *position, inside, ctx, actions);
             }
-        }
-        if let Event::TouchEnd { position, .. } = &event {
-            let inside = self.hittest(path, *position, ctx).is_some();
-            self.func.call(ctx, *position, inside, actions);
+            Event::MouseLeftWindow => {
+                if ctx.hovered.contains(&vid) {
+                    self.update(vid, Point::ZERO, false, ctx, actions);
+                }
+            }
+            _ => (),
         }
         path.push(0);
         self.child.process(event, path, ctx, actions);
@@ -101,8 +145,38 @@ where
     }
 
     fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
         path.push(0);
         self.child.gc(path, ctx, map);
         path.pop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_hover_enter_leave() {
+        let ui = state(
+            || vec![],
+            |events, _| {
+                rectangle()
+                    .sized((50.0, 50.0))
+                    .hover(move |ctx, inside| ctx[events].push(inside))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<Vec<bool>>(&vec![0]);
+
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_move((20.0, 20.0));
+        harness.mouse_move((80.0, 80.0));
+        harness.mouse_move((90.0, 90.0));
+        harness.mouse_move((30.0, 30.0));
+        harness.process(&Event::MouseLeftWindow);
+
+        assert_eq!(harness.cx()[s], vec![true, false, true, false]);
+    }
+}
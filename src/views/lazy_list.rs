@@ -0,0 +1,345 @@
+use vello::kurbo::Affine;
+
+use crate::*;
+use std::any::Any;
+use std::hash::Hash;
+use std::ops::Range;
+
+/// Extra space above and below the viewport in which rows are kept,
+/// so they're ready before they scroll into view.
+const LAZY_LIST_OVERSCAN: f64 = 50.0;
+
+/// Identifies the rows of a `lazy_list`.
+///
+/// Implemented for `usize`, meaning rows `0..n`, and for `Vec<ID>`
+/// where each row is keyed by the hash of its id.
+pub trait ListIds: Clone + 'static {
+    type Id: Hash + Clone + 'static;
+
+    fn len(&self) -> usize;
+
+    fn id(&self, index: usize) -> Self::Id;
+}
+
+impl ListIds for usize {
+    type Id = usize;
+
+    fn len(&self) -> usize {
+        *self
+    }
+
+    fn id(&self, index: usize) -> usize {
+        index
+    }
+}
+
+impl<ID: Hash + Clone + 'static> ListIds for Vec<ID> {
+    type Id = ID;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn id(&self, index: usize) -> ID {
+        self[index].clone()
+    }
+}
+
+/// Gives the height of each row of a `lazy_list`.
+///
+/// Implemented for `f64`, for rows of the same height, and for
+/// `Fn(usize) -> f64` closures which return the height of a row.
+pub trait RowHeight: Clone + 'static {
+    fn height(&self, index: usize) -> f64;
+}
+
+impl RowHeight for f64 {
+    fn height(&self, _index: usize) -> f64 {
+        *self
+    }
+}
+
+impl<F: Fn(usize) -> f64 + Clone + 'static> RowHeight for F {
+    fn height(&self, index: usize) -> f64 {
+        self(index)
+    }
+}
+
+#[derive(Default)]
+struct LazyListState {
+    /// Top of each row, followed by the total height.
+    offsets: Vec<f64>,
+
+    /// Rows instantiated by the last layout.
+    visible: Range<usize>,
+
+    /// Path components of the visible rows, so rows which
+    /// scroll away can be forgotten.
+    keys: Vec<u64>,
+}
+
+/// Struct for `lazy_list`.
+#[derive(Clone)]
+pub struct LazyList<IDS, H, F> {
+    ids: IDS,
+    heights: H,
+    func: F,
+}
+
+impl<IDS, H, V, F> LazyList<IDS, H, F>
+where
+    IDS: ListIds,
+    H: RowHeight,
+    V: View,
+    F: Fn(&IDS::Id) -> V + Clone + 'static,
+{
+    /// Rows to visit, in order. Clamped in case the ids
+    /// have changed since the last layout.
+    fn visible(&self, path: &IdPath, ctx: &mut Context) -> Range<usize> {
+        let id = ctx.view_id(path);
+        ctx.init_state(id, &LazyListState::default);
+        let visible = ctx.state_mut::<LazyListState>(id).visible.clone();
+        let n = self.ids.len();
+        visible.start.min(n)..visible.end.min(n)
+    }
+}
+
+impl<IDS, H, V, F> DynView for LazyList<IDS, H, F>
+where
+    IDS: ListIds,
+    H: RowHeight,
+    V: View,
+    F: Fn(&IDS::Id) -> V + Clone + 'static,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        for i in self.visible(path, ctx).rev() {
+            let child = self.ids.id(i);
+            path.push(hh(&child));
+            let offset = ctx.get_layout(path).offset;
+            ((self.func)(&child)).process(&event.offset(-offset), path, ctx, actions);
+            path.pop();
+        }
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        let mut scene = Scene::new();
+        for i in self.visible(path, ctx) {
+            let child = self.ids.id(i);
+            path.push(hh(&child));
+            let offset = ctx.get_layout(path).offset;
+            let child_scene = ((self.func)(&child)).draw(path, ctx);
+            scene.append(&child_scene, Some(Affine::translate(offset)));
+            path.pop();
+        }
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        let id = args.ctx.view_id(path);
+        args.ctx.init_state(id, &LazyListState::default);
+
+        let n = self.ids.len();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut y = 0.0;
+        for i in 0..n {
+            offsets.push(y);
+            y += self.heights.height(i);
+        }
+        offsets.push(y);
+
+        // Outside of a scroll view, show what fits in the space we're given.
+        let viewport = args
+            .ctx
+            .viewport
+            .unwrap_or(Rect::from_origin_size(Point::ZERO, args.size));
+        let top = viewport.y0 - LAZY_LIST_OVERSCAN;
+        let bottom = viewport.y1 + LAZY_LIST_OVERSCAN;
+        let first = offsets[1..].partition_point(|&end| end <= top);
+        let last = offsets[..n].partition_point(|&start| start < bottom);
+        let visible = first..last.max(first);
+
+        let keys: Vec<u64> = visible.clone().map(|i| hh(&self.ids.id(i))).collect();
+
+        // Forget rows which are no longer visible.
+        let old_keys = std::mem::take(&mut args.ctx.state_mut::<LazyListState>(id).keys);
+        for key in old_keys {
+            if !keys.contains(&key) {
+                path.push(key);
+                args.ctx.forget_subtree(path);
+                path.pop();
+            }
+        }
+
+        let mut width: f64 = 0.0;
+        for i in visible.clone() {
+            let child = self.ids.id(i);
+            let row_size = Size::new(args.size.width, offsets[i + 1] - offsets[i]);
+            path.push(hh(&child));
+            let child_size = ((self.func)(&child)).layout(path, &mut args.with_size(row_size));
+            args.ctx.set_layout_offset(path, Vec2::new(0.0, offsets[i]));
+            path.pop();
+            width = width.max(child_size.width);
+        }
+
+        let s = args.ctx.state_mut::<LazyListState>(id);
+        s.offsets = offsets;
+        s.visible = visible;
+        s.keys = keys;
+
+        if args.size.width.is_finite() {
+            width = args.size.width;
+        }
+        Size::new(width, y)
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        let mut hit = None;
+        for i in self.visible(path, ctx) {
+            let child = self.ids.id(i);
+            path.push(hh(&child));
+            let offset = ctx.get_layout(path).offset;
+            if let Some(h) = ((self.func)(&child)).hittest(path, pt - offset, ctx) {
+                hit = Some(h)
+            }
+            path.pop();
+        }
+        hit
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        for i in self.visible(path, ctx) {
+            let child = self.ids.id(i);
+            path.push(hh(&child));
+            ((self.func)(&child)).commands(path, ctx, cmds);
+            path.pop();
+        }
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        for i in self.visible(path, ctx) {
+            let child = self.ids.id(i);
+            path.push(hh(&child));
+            map.push(ctx.view_id(path));
+            ((self.func)(&child)).gc(path, ctx, map);
+            path.pop();
+        }
+    }
+}
+
+/// A vertical list which only creates views for the rows that are visible.
+///
+/// Put it directly inside a `vscroll` (or `scroll`). Rows outside the
+/// scroll view's viewport aren't laid out, drawn or sent events, and
+/// their layout and state are dropped when they scroll away. Their ids
+/// are reused for the rows which scroll into view.
+///
+/// `ids` is either a row count or a `Vec` of ids which implement `Hash`,
+/// so rows keep their state when the list is reordered.
+///
+/// `heights` is either a fixed row height or a function from row index to height.
+///
+/// ```no_run
+/// # use vui::*;
+/// vscroll(lazy_list(10_000, 20.0, |i| text(&format!("row {i}"))));
+/// ```
+pub fn lazy_list<IDS: ListIds, H: RowHeight, V: View, F: Fn(&IDS::Id) -> V + Clone + 'static>(
+    ids: IDS,
+    heights: H,
+    f: F,
+) -> LazyList<IDS, H, F> {
+    LazyList {
+        ids,
+        heights,
+        func: f,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lazy_list_visible_rows() {
+        let ui = vscroll(lazy_list(10_000, 20.0, |_| rectangle()));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let row = |i: usize| vec![0, 0, hh(&i)];
+
+        assert_eq!(
+            harness.find_view_rect(&row(0)),
+            Some(Rect::new(0.0, 0.0, 100.0, 20.0))
+        );
+        // The last row within the overscan.
+        assert!(harness.find_view_rect(&row(7)).is_some());
+        assert!(harness.find_view_rect(&row(8)).is_none());
+
+        harness.scroll((50.0, 50.0), (0.0, -500.0));
+
+        assert!(harness.find_view_rect(&row(0)).is_none());
+        assert!(harness.find_view_rect(&row(21)).is_none());
+        assert_eq!(
+            harness.find_view_rect(&row(25)),
+            Some(Rect::new(0.0, 0.0, 100.0, 20.0))
+        );
+        assert!(harness.find_view_rect(&row(32)).is_some());
+        assert!(harness.find_view_rect(&row(33)).is_none());
+    }
+
+    #[test]
+    fn test_lazy_list_recycles_ids() {
+        let ui = vscroll(lazy_list(10_000, 20.0, |i: &usize| {
+            let i = *i;
+            state(move || i, |_, _| rectangle())
+        }));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let row = |i: usize| vec![0, 0, hh(&i)];
+        let old: Vec<ViewId> = (0..8).map(|i| harness.view_id(&row(i))).collect();
+
+        harness.scroll((50.0, 50.0), (0.0, -500.0));
+
+        // New rows take the ids of the rows which scrolled away,
+        // but not their state.
+        assert!(old.contains(&harness.view_id(&row(25))));
+        let s = harness.state_handle::<usize>(&row(25));
+        assert_eq!(harness.cx()[s], 25);
+    }
+
+    #[test]
+    fn test_lazy_list_keyed_rows() {
+        let ui = state(
+            || vec!["a", "b", "c"],
+            |ids, cx| {
+                lazy_list(
+                    cx[ids].clone(),
+                    |i: usize| 10.0 * (i + 1) as f64,
+                    |_| rectangle(),
+                )
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let ids = harness.state_handle::<Vec<&str>>(&vec![0]);
+
+        assert_eq!(
+            harness.find_view_rect(&vec![0, 0, hh(&"b")]),
+            Some(Rect::new(0.0, 10.0, 100.0, 30.0))
+        );
+
+        let b = harness.view_id(&vec![0, 0, hh(&"b")]);
+        harness.cx_mut()[ids] = vec!["b", "a", "c"];
+        harness.update();
+
+        // "b" keeps its id but moves to the top.
+        assert_eq!(harness.view_id(&vec![0, 0, hh(&"b")]), b);
+        assert_eq!(
+            harness.find_view_rect(&vec![0, 0, hh(&"b")]),
+            Some(Rect::new(0.0, 0.0, 100.0, 10.0))
+        );
+    }
+}
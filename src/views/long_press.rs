@@ -0,0 +1,124 @@
+use crate::*;
+use std::any::Any;
+
+/// How long a touch is held before it's a long press.
+const LONG_PRESS_DURATION: f64 = 0.5;
+
+/// How far a touch can move and still be a long press.
+const LONG_PRESS_SLOP: f64 = 10.0;
+
+#[derive(Default)]
+pub struct LongPressState {
+    down: Option<usize>,
+    start: Point,
+
+    /// Clock time the touch went down.
+    since: f64,
+}
+
+/// Recognizes a touch held in place.
+#[derive(Clone)]
+pub struct LongPressRecognizer<F> {
+    func: F,
+}
+
+impl<F> LongPressRecognizer<F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<A: 'static, F: Fn(&mut Context, Point) -> A + Clone + 'static> Recognizer
+    for LongPressRecognizer<F>
+{
+    type State = LongPressState;
+
+    fn begin(
+        &self,
+        state: &mut LongPressState,
+        id: usize,
+        position: Point,
+        _button: Option<MouseButton>,
+        now: f64,
+    ) -> Option<Recognition> {
+        if state.down.is_some() {
+            return None;
+        }
+        state.down = Some(id);
+        state.start = position;
+        state.since = now;
+        Some(Recognition::Possible)
+    }
+
+    fn update(&self, state: &mut LongPressState, event: &Event, now: f64) -> Recognition {
+        match event {
+            Event::TouchMove { position, .. } => {
+                if (*position - state.start).hypot() > LONG_PRESS_SLOP {
+                    return Recognition::Reject;
+                }
+            }
+            Event::TouchEnd { .. } => return Recognition::Reject,
+            Event::Anim => {
+                if now - state.since >= LONG_PRESS_DURATION {
+                    return Recognition::Accept;
+                }
+            }
+            _ => (),
+        }
+        Recognition::Possible
+    }
+
+    fn recognized(
+        &self,
+        ctx: &mut Context,
+        state: &LongPressState,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        actions.push(Box::new((self.func)(ctx, state.start)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_long_press() {
+        let ui = state(
+            || vec![],
+            |events, _| {
+                rectangle()
+                    .tap(move |cx| cx[events].push("tap"))
+                    .long_press(move |cx, _| cx[events].push("long press"))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<Vec<&str>>(&vec![0]);
+
+        harness.mouse_down((50.0, 50.0));
+        harness.advance(0.6);
+        assert_eq!(harness.cx()[s], vec!["long press"]);
+        harness.mouse_up((50.0, 50.0));
+
+        // A quick tap isn't a long press.
+        harness.tap_at((50.0, 50.0));
+        harness.advance(0.6);
+        assert_eq!(harness.cx()[s], vec!["long press", "tap"]);
+
+        // Nor is a touch which wanders off.
+        harness.mouse_down((50.0, 50.0));
+        harness.mouse_move((80.0, 50.0));
+        harness.advance(0.6);
+        assert_eq!(harness.cx()[s], vec!["long press", "tap"]);
+        harness.mouse_up((80.0, 50.0));
+
+        // Time spent idle before the touch doesn't count towards holding it.
+        harness.idle(5.0);
+        harness.mouse_down((50.0, 50.0));
+        harness.advance(0.3);
+        assert_eq!(harness.cx()[s], vec!["long press", "tap"]);
+        harness.advance(0.3);
+        assert_eq!(harness.cx()[s], vec!["long press", "tap", "long press"]);
+    }
+}
@@ -1,5 +1,7 @@
 mod anim;
 pub use anim::*;
+mod animation;
+pub use animation::*;
 // mod anyview;
 // pub use anyview::*;
 mod background;
@@ -10,6 +12,8 @@ mod canvas;
 pub use canvas::*;
 mod clip;
 pub use clip::*;
+mod close_request;
+pub use close_request::*;
 mod command;
 pub use command::*;
 mod cond;
@@ -26,6 +30,8 @@ mod focus;
 pub use focus::*;
 mod geom;
 pub use geom::*;
+mod gesture;
+pub use gesture::*;
 mod handle;
 pub use handle::*;
 mod hover;
@@ -34,18 +40,28 @@ mod key;
 pub use key::*;
 mod knob;
 pub use knob::*;
+mod lazy_list;
+pub use lazy_list::*;
 mod list;
 pub use list::*;
+mod long_press;
+pub use long_press::*;
 mod map;
 pub use map::*;
 mod modview;
 pub use modview::*;
 mod offset;
 pub use offset::*;
+mod opacity;
+pub use opacity::*;
 mod padding;
 pub use padding::*;
 mod redux;
 pub use redux::*;
+mod scroll;
+pub use scroll::*;
+mod scroll_gesture;
+pub use scroll_gesture::*;
 mod shapes;
 pub use shapes::*;
 mod size;
@@ -59,15 +75,21 @@ pub use stack::*;
 mod stack_layout;
 mod state;
 pub use state::*;
+mod swipe;
+pub use swipe::*;
 mod tap;
 pub use tap::*;
+mod task;
+pub use task::*;
 mod touch;
 pub use touch::*;
-// mod text_editor;
-// pub use text_editor::*;
+mod text_editor;
+pub use text_editor::*;
 mod text;
 pub use text::*;
 mod toggle;
 pub use toggle::*;
+mod two_finger;
+pub use two_finger::*;
 mod window;
 pub use window::*;
@@ -20,15 +20,16 @@ where
         ctx: &mut Context,
         actions: &mut Vec<Box<dyn Any>>,
     ) {
+        let offset = self.offset(path, ctx);
         path.push(0);
         self.child
-            .process(&event.offset(-self.offset), path, ctx, actions);
+            .process(&event.offset(-offset), path, ctx, actions);
         path.pop();
     }
 
     fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
         let mut scene = Scene::new();
-        let translate = Affine::translate(self.offset);
+        let translate = Affine::translate(self.offset(path, ctx));
         path.push(0);
         scene.append(&self.child.draw(path, ctx), Some(translate));
         path.pop();
@@ -43,8 +44,9 @@ where
     }
 
     fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        let offset = self.offset(path, ctx);
         path.push(0);
-        let hit_id = self.child.hittest(path, pt - self.offset, ctx);
+        let hit_id = self.child.hittest(path, pt - offset, ctx);
         path.pop();
         hit_id
     }
@@ -56,6 +58,7 @@ where
     }
 
     fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
         path.push(0);
         self.child.gc(path, ctx, map);
         path.pop();
@@ -69,4 +72,10 @@ where
     pub fn new(child: V, offset: Vec2) -> Self {
         Self { child, offset }
     }
+
+    /// The offset, part way to a new one while that's animating.
+    fn offset(&self, path: &IdPath, ctx: &mut Context) -> Vec2 {
+        let id = ctx.view_id(path);
+        ctx.animate(id, "offset", self.offset)
+    }
 }
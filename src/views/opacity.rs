@@ -0,0 +1,92 @@
+use crate::*;
+use kurbo::Affine;
+use peniko::Mix;
+use std::any::Any;
+
+/// Struct for the `opacity` modifier.
+#[derive(Clone)]
+pub struct Opacity<V> {
+    child: V,
+    opacity: f64,
+}
+
+impl<V> DynView for Opacity<V>
+where
+    V: View,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        path.push(0);
+        self.child.process(event, path, ctx, actions);
+        path.pop();
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        let id = ctx.view_id(path);
+        let opacity = ctx.animate(id, "opacity", self.opacity);
+        let rect = ctx.get_layout(path).rect;
+        path.push(0);
+        let child = self.child.draw(path, ctx);
+        path.pop();
+
+        if opacity >= 1.0 {
+            return child;
+        }
+        let mut scene = Scene::new();
+        scene.push_layer(Mix::Normal, opacity as f32, Affine::IDENTITY, &rect);
+        scene.append(&child, None);
+        scene.pop_layer();
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        args.ctx.update_layout(
+            path,
+            LayoutBox {
+                rect: Rect::from_origin_size(Point::ZERO, size),
+                offset: Vec2::ZERO,
+            },
+        );
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
+
+impl<V> Opacity<V>
+where
+    V: View,
+{
+    pub fn new(child: V, opacity: f64) -> Self {
+        Self {
+            child,
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+}
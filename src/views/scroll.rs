@@ -0,0 +1,647 @@
+use crate::*;
+use kurbo::{Affine, RoundedRect};
+use std::any::Any;
+
+const SCROLL_BAR_WIDTH: f64 = 6.0;
+const SCROLL_BAR_MARGIN: f64 = 2.0;
+const SCROLL_BAR_MIN_LENGTH: f64 = 20.0;
+
+/// Seconds the scroll bars stay visible after scrolling stops.
+const SCROLL_BAR_HOLD: f64 = 1.0;
+
+/// Seconds the scroll bars take to fade out.
+const SCROLL_BAR_FADE: f64 = 0.3;
+
+/// Fraction of the velocity kept each frame after a drag is released.
+const SCROLL_DECELERATION: f64 = 0.95;
+
+/// Inertial scrolling stops below this speed, in points per frame.
+const SCROLL_MIN_VELOCITY: f64 = 0.1;
+
+/// Fraction of the viewport scrolled by page up and page down.
+const SCROLL_PAGE_FRACTION: f64 = 0.9;
+
+/// How far a touch moves along a scrollable axis before it drags
+/// the content, so views inside can still be tapped.
+const SCROLL_DRAG_SLOP: f64 = 10.0;
+
+/// Velocities and deceleration are per frame of this length, and
+/// scaled to the measured frame time.
+const SCROLL_FRAME_TIME: f64 = 1.0 / 60.0;
+
+#[derive(Clone, Default)]
+struct ScrollState {
+    /// Point in the content shown at the top left of the viewport.
+    offset: Vec2,
+    content_size: Size,
+    viewport_size: Size,
+
+    /// Offset change per frame after a drag is released.
+    velocity: Vec2,
+
+    /// Is the pointer over the view? Keyboard paging only applies then.
+    hovered: bool,
+
+    /// Seconds since the view last scrolled, for hiding the bars.
+    idle: f64,
+
+    /// Where the touch scrolling the view began.
+    drag_start: Point,
+
+    /// Has the touch moved far enough to scroll?
+    dragging: bool,
+}
+
+impl ScrollState {
+    fn max_offset(&self) -> Vec2 {
+        Vec2::new(
+            (self.content_size.width - self.viewport_size.width).max(0.0),
+            (self.content_size.height - self.viewport_size.height).max(0.0),
+        )
+    }
+
+    fn bar_alpha(&self) -> f64 {
+        1.0 - ((self.idle - SCROLL_BAR_HOLD) / SCROLL_BAR_FADE).clamp(0.0, 1.0)
+    }
+}
+
+/// Struct for `scroll`, `hscroll` and `vscroll`.
+#[derive(Clone)]
+pub struct ScrollView<V> {
+    child: V,
+    horizontal: bool,
+    vertical: bool,
+}
+
+impl<V> ScrollView<V>
+where
+    V: View,
+{
+    pub fn new(child: V, horizontal: bool, vertical: bool) -> Self {
+        Self {
+            child,
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// Moves the content, returning false if it was already there.
+    fn scroll_to(&self, path: &mut IdPath, ctx: &mut Context, offset: Vec2) -> bool {
+        let id = ctx.view_id(path);
+        let s = ctx.state_mut::<ScrollState>(id);
+        let max = s.max_offset();
+        let offset = Vec2::new(
+            if self.horizontal {
+                offset.x.clamp(0.0, max.x)
+            } else {
+                0.0
+            },
+            if self.vertical {
+                offset.y.clamp(0.0, max.y)
+            } else {
+                0.0
+            },
+        );
+        if offset == s.offset {
+            return false;
+        }
+
+        let s = &mut ctx[StateHandle::<ScrollState>::new(id)];
+        s.offset = offset;
+        s.idle = 0.0;
+
+        path.push(0);
+        ctx.set_layout_offset(path, -offset);
+        path.pop();
+        true
+    }
+
+    /// Runs inertia and fades the scroll bars.
+    fn animate(&self, path: &mut IdPath, ctx: &mut Context) {
+        let id = ctx.view_id(path);
+        let s = ctx.state_mut::<ScrollState>(id).clone();
+        let dt = ctx.delta_time();
+        let frames = dt / SCROLL_FRAME_TIME;
+
+        // Keep the velocity while dragging so it can be used on release.
+        if !ctx.touches.contains(&id) && s.velocity != Vec2::ZERO {
+            let velocity = if s.velocity.hypot() > SCROLL_MIN_VELOCITY
+                && self.scroll_to(path, ctx, s.offset + s.velocity * frames)
+            {
+                s.velocity * SCROLL_DECELERATION.powf(frames)
+            } else {
+                Vec2::ZERO
+            };
+            ctx.state_mut::<ScrollState>(id).velocity = velocity;
+            if velocity != Vec2::ZERO {
+                ctx.request_frame();
+            }
+        }
+
+        if s.idle < SCROLL_BAR_HOLD + SCROLL_BAR_FADE {
+            ctx.state_mut::<ScrollState>(id).idle += dt;
+            ctx.request_frame();
+        }
+    }
+
+    /// Scrolls with touch `touch` once it has moved past the slop. Gestures
+    /// inside can't have the touch once it has moved the content.
+    fn drag(
+        &self,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        touch: usize,
+        position: Point,
+        delta: Vec2,
+    ) {
+        let id = ctx.view_id(path);
+        let s = ctx.state_mut::<ScrollState>(id).clone();
+
+        // Catch up with the movement within the slop once we start.
+        let delta = if s.dragging {
+            delta
+        } else {
+            let max = s.max_offset();
+            let moved = position - s.drag_start;
+            let along = Vec2::new(
+                if self.horizontal && max.x > 0.0 {
+                    moved.x
+                } else {
+                    0.0
+                },
+                if self.vertical && max.y > 0.0 {
+                    moved.y
+                } else {
+                    0.0
+                },
+            );
+            if along.hypot() <= SCROLL_DRAG_SLOP {
+                return;
+            }
+            ctx.state_mut::<ScrollState>(id).dragging = true;
+            moved
+        };
+
+        if self.scroll_to(path, ctx, s.offset - delta) {
+            ctx.gestures.cancel_touch(touch);
+        }
+        ctx.state_mut::<ScrollState>(id).velocity = -delta;
+    }
+
+    /// Handles page up, page down, home and end.
+    fn page(&self, path: &mut IdPath, ctx: &mut Context, key: &Key) -> bool {
+        let id = ctx.view_id(path);
+        let s = ctx.state_mut::<ScrollState>(id).clone();
+
+        // Page along the vertical axis unless we only scroll horizontally.
+        let (axis, page) = if self.vertical {
+            (Vec2::new(0.0, 1.0), s.viewport_size.height)
+        } else {
+            (Vec2::new(1.0, 0.0), s.viewport_size.width)
+        };
+        let page = page * SCROLL_PAGE_FRACTION;
+        let along = s.offset.dot(axis);
+        let target = match key {
+            Key::PageUp => along - page,
+            Key::PageDown => along + page,
+            Key::Home => 0.0,
+            Key::End => s.max_offset().dot(axis),
+            _ => return false,
+        };
+
+        let offset = s.offset + axis * (target - along);
+        self.scroll_to(path, ctx, offset)
+    }
+
+    fn bars(&self, s: &ScrollState, rect: Rect) -> Vec<Rect> {
+        let mut bars = vec![];
+        let max = s.max_offset();
+
+        if self.vertical && max.y > 0.0 {
+            let track = rect.height() - 2.0 * SCROLL_BAR_MARGIN;
+            let length = (track * s.viewport_size.height / s.content_size.height)
+                .max(SCROLL_BAR_MIN_LENGTH)
+                .min(track);
+            let y0 = rect.y0 + SCROLL_BAR_MARGIN + s.offset.y / max.y * (track - length);
+            bars.push(Rect::new(
+                rect.x1 - SCROLL_BAR_MARGIN - SCROLL_BAR_WIDTH,
+                y0,
+                rect.x1 - SCROLL_BAR_MARGIN,
+                y0 + length,
+            ));
+        }
+
+        if self.horizontal && max.x > 0.0 {
+            let track = rect.width() - 2.0 * SCROLL_BAR_MARGIN;
+            let length = (track * s.viewport_size.width / s.content_size.width)
+                .max(SCROLL_BAR_MIN_LENGTH)
+                .min(track);
+            let x0 = rect.x0 + SCROLL_BAR_MARGIN + s.offset.x / max.x * (track - length);
+            bars.push(Rect::new(
+                x0,
+                rect.y1 - SCROLL_BAR_MARGIN - SCROLL_BAR_WIDTH,
+                x0 + length,
+                rect.y1 - SCROLL_BAR_MARGIN,
+            ));
+        }
+
+        bars
+    }
+}
+
+impl<V> DynView for ScrollView<V>
+where
+    V: View,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        let vid = ctx.view_id(path);
+        ctx.init_state(vid, &ScrollState::default);
+        let rect = ctx.get_layout(path).rect;
+        let offset = ctx.state_mut::<ScrollState>(vid).offset;
+
+        // Content outside the viewport is clipped, so it can't be touched.
+        let inside = match event {
+            Event::TouchBegin { position, .. } => rect.contains(*position),
+            _ => true,
+        };
+        if inside {
+            path.push(0);
+            self.child
+                .process(&event.offset(offset), path, ctx, actions);
+            path.pop();
+        }
+
+        // The content gets events first, so drags only scroll
+        // if nothing inside has taken the touch.
+        match event {
+            Event::TouchBegin { id, position } => {
+                if rect.contains(*position) && ctx.capture_touch(*id, vid) {
+                    ctx.previous_position[*id] = *position;
+                    let s = ctx.state_mut::<ScrollState>(vid);
+                    s.velocity = Vec2::ZERO;
+                    s.drag_start = *position;
+                    s.dragging = false;
+                }
+            }
+            Event::TouchMove {
+                id,
+                position,
+                delta,
+            } => {
+                if ctx.has_touch(*id, vid) {
+                    self.drag(path, ctx, *id, *position, *delta);
+                    ctx.previous_position[*id] = *position;
+                }
+            }
+            Event::PointerMove { position, delta } => {
+                ctx.state_mut::<ScrollState>(vid).hovered = rect.contains(*position);
+                // The mouse is touch 0.
+                if ctx.has_touch(0, vid) {
+                    self.drag(path, ctx, 0, *position, *delta);
+                    ctx.previous_position[0] = *position;
+                }
+            }
+            Event::TouchEnd { id, .. } => {
+                ctx.release_touch(*id, vid);
+            }
+            Event::MouseLeftWindow => {
+                ctx.state_mut::<ScrollState>(vid).hovered = false;
+            }
+            Event::Scroll {
+                position, delta, ..
+            } => {
+                // Leave the event for an outer scroll view if we can't move.
+                if !ctx.event_handled
+                    && rect.contains(*position)
+                    && self.scroll_to(path, ctx, offset - *delta)
+                {
+                    ctx.event_handled = true;
+                }
+            }
+            Event::Key(key) => {
+                if !ctx.event_handled
+                    && ctx.state_mut::<ScrollState>(vid).hovered
+                    && self.page(path, ctx, key)
+                {
+                    ctx.event_handled = true;
+                }
+            }
+            Event::Anim => self.animate(path, ctx),
+            _ => (),
+        }
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        let id = ctx.view_id(path);
+        ctx.init_state(id, &ScrollState::default);
+        let s = ctx.state_mut::<ScrollState>(id).clone();
+        let rect = ctx.get_layout(path).rect;
+
+        path.push(0);
+        let content = self.child.draw(path, ctx);
+        path.pop();
+        let mut scene = clip_scene(rect, &content, Affine::translate(-s.offset));
+
+        let alpha = s.bar_alpha();
+        if alpha > 0.0 {
+            let color = MEDIUM_GRAY.multiply_alpha(alpha as f32);
+            for bar in self.bars(&s, rect) {
+                scene.fill(
+                    peniko::Fill::NonZero,
+                    Affine::IDENTITY,
+                    color,
+                    None,
+                    &RoundedRect::from_rect(bar, SCROLL_BAR_WIDTH / 2.0),
+                );
+            }
+        }
+
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        let id = args.ctx.view_id(path);
+        args.ctx.init_state(id, &ScrollState::default);
+
+        let proposed = Size::new(
+            if self.horizontal {
+                f64::INFINITY
+            } else {
+                args.size.width
+            },
+            if self.vertical {
+                f64::INFINITY
+            } else {
+                args.size.height
+            },
+        );
+
+        // Let lazy content know what's visible, and make any state
+        // views inside depend on the scroll position.
+        let offset = args.ctx.state_mut::<ScrollState>(id).offset;
+        let prev_viewport = args
+            .ctx
+            .viewport
+            .replace(Rect::from_origin_size(offset.to_point(), args.size));
+        args.ctx.id_stack.push(id);
+
+        path.push(0);
+        let mut content_size = self.child.layout(path, &mut args.with_size(proposed));
+
+        args.ctx.id_stack.pop();
+        args.ctx.viewport = prev_viewport;
+
+        // Views which fill the space they're offered can't be scrolled.
+        if !content_size.width.is_finite() {
+            content_size.width = args.size.width;
+        }
+        if !content_size.height.is_finite() {
+            content_size.height = args.size.height;
+        }
+
+        let s = args.ctx.state_mut::<ScrollState>(id);
+        s.content_size = content_size;
+        s.viewport_size = args.size;
+        let max = s.max_offset();
+        s.offset = Vec2::new(s.offset.x.min(max.x), s.offset.y.min(max.y));
+        let offset = s.offset;
+
+        args.ctx.set_layout_offset(path, -offset);
+        path.pop();
+
+        args.ctx.update_layout(
+            path,
+            LayoutBox {
+                rect: Rect::from_origin_size(Point::ZERO, args.size),
+                offset: Vec2::ZERO,
+            },
+        );
+        args.size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        let id = ctx.view_id(path);
+        let rect = ctx.get_layout(path).rect;
+
+        if rect.contains(pt) {
+            ctx.init_state(id, &ScrollState::default);
+            let offset = ctx.state_mut::<ScrollState>(id).offset;
+            path.push(0);
+            let hit_id = self.child.hittest(path, pt + offset, ctx);
+            path.pop();
+            hit_id.or(Some(id))
+        } else {
+            None
+        }
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
+
+/// Scrolls a view horizontally and vertically.
+///
+/// The child is laid out with unbounded width and height, so it should
+/// have a size of its own (views which fill the available space are
+/// given the size of the scroll view instead). Scrolls with the mouse
+/// wheel, by dragging, and with page up, page down, home and end while
+/// the pointer is over it.
+pub fn scroll<V: View>(view: V) -> ScrollView<V> {
+    ScrollView::new(view, true, true)
+}
+
+/// Scrolls a view horizontally. See `scroll`.
+pub fn hscroll<V: View>(view: V) -> ScrollView<V> {
+    ScrollView::new(view, true, false)
+}
+
+/// Scrolls a view vertically. See `scroll`.
+pub fn vscroll<V: View>(view: V) -> ScrollView<V> {
+    ScrollView::new(view, false, true)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn scroll_offset<V: View>(harness: &mut TestHarness<V>) -> Vec2 {
+        let id = harness.view_id(&vec![0]);
+        harness.cx()[StateHandle::<ScrollState>::new(id)].offset
+    }
+
+    #[test]
+    fn test_scroll_wheel() {
+        let ui = vscroll(rectangle().sized((100.0, 300.0)));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+
+        harness.scroll((50.0, 50.0), (0.0, -50.0));
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 50.0));
+        assert_eq!(
+            harness.find_view_rect(&vec![0, 0, 0]),
+            Some(Rect::new(0.0, -50.0, 100.0, 250.0))
+        );
+
+        // Clamped to the end of the content.
+        harness.scroll((50.0, 50.0), (-10.0, -1000.0));
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 200.0));
+    }
+
+    #[test]
+    fn test_scroll_bar_fade_keeps_layout() {
+        let ui = vscroll(rectangle().sized((100.0, 300.0)));
+        let mut ctx = Context::new();
+        let size = (100.0, 100.0).into();
+        let mut path = vec![0];
+        ui.layout(
+            &mut path,
+            &mut LayoutArgs {
+                size,
+                ctx: &mut ctx,
+            },
+        );
+
+        ctx.process(
+            &ui,
+            &Event::Scroll {
+                position: (50.0, 50.0).into(),
+                delta: (0.0, -50.0).into(),
+                phase: GestureState::Changed,
+            },
+        );
+        ctx.update(&ui, size);
+        assert!(!ctx.dirty);
+
+        // Fading the scroll bars asks for frames without a relayout.
+        ui.process(&Event::Anim, &mut path, &mut ctx, &mut vec![]);
+        assert!(!ctx.dirty);
+        assert_eq!(ctx.next_frame_in(), Some(0.0));
+    }
+
+    #[test]
+    fn test_scroll_nested() {
+        let ui = vscroll(hscroll(rectangle().sized((300.0, 100.0))).sized((100.0, 300.0)));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+
+        // Vertical scrolling passes through the horizontal scroll view.
+        harness.scroll((50.0, 50.0), (0.0, -20.0));
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 20.0));
+
+        harness.scroll((50.0, 50.0), (-20.0, 0.0));
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 20.0));
+        let inner = harness.view_id(&vec![0, 0, 0]);
+        assert_eq!(
+            harness.cx()[StateHandle::<ScrollState>::new(inner)].offset,
+            Vec2::new(20.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_scroll_drag_inertia() {
+        let ui = vscroll(rectangle().sized((100.0, 300.0)));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+
+        harness.drag((50.0, 80.0), (50.0, 30.0), 5);
+
+        // 50 from the drag, and then one frame of inertia. Frame times
+        // are measured from the clock, so allow for rounding.
+        let offset = scroll_offset(&mut harness);
+        assert_eq!(offset.x, 0.0);
+        assert!((offset.y - 60.0).abs() < 1e-9);
+    }
+
+    fn tap_rows(height: f64) -> impl View {
+        state(
+            || 0,
+            move |taps, _| {
+                vscroll(vstack((
+                    rectangle()
+                        .sized((100.0, height))
+                        .tap(move |cx| cx[taps] += 1),
+                    rectangle()
+                        .sized((100.0, height))
+                        .tap(move |cx| cx[taps] += 1),
+                )))
+            },
+        )
+    }
+
+    #[test]
+    fn test_scroll_drag_cancels_taps() {
+        let mut harness = TestHarness::new(tap_rows(150.0), (100.0, 100.0));
+        let taps = harness.state_handle::<i32>(&vec![0]);
+
+        // Past the drag slop but not the tap slop, so only the scroll view stops the tap.
+        harness.drag((50.0, 50.0), (50.0, 35.0), 3);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[taps], 0);
+        let scroll = harness.view_id(&vec![0, 0]);
+        let offset = harness.cx()[StateHandle::<ScrollState>::new(scroll)].offset;
+        assert!(offset.y > 0.0);
+
+        // Taps still work once the scrolling has stopped.
+        harness.advance(1.0);
+        harness.tap_at((50.0, 50.0));
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[taps], 1);
+    }
+
+    #[test]
+    fn test_scroll_keeps_taps() {
+        let mut harness = TestHarness::new(tap_rows(150.0), (100.0, 100.0));
+        let taps = harness.state_handle::<i32>(&vec![0]);
+
+        // A little jitter while clicking doesn't scroll.
+        harness.drag((50.0, 50.0), (51.0, 49.0), 1);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[taps], 1);
+
+        // Nor does moving along an axis which can't scroll.
+        harness.drag((30.0, 50.0), (45.0, 50.0), 3);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[taps], 2);
+
+        // Content which fits can't scroll at all.
+        let mut harness = TestHarness::new(tap_rows(40.0), (100.0, 100.0));
+        let taps = harness.state_handle::<i32>(&vec![0]);
+        harness.drag((50.0, 20.0), (50.0, 35.0), 3);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[taps], 1);
+    }
+
+    #[test]
+    fn test_scroll_paging() {
+        let ui = vscroll(rectangle().sized((100.0, 300.0)));
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+
+        // Keys are ignored unless the pointer is over the view.
+        harness.key(Key::PageDown);
+        assert_eq!(scroll_offset(&mut harness), Vec2::ZERO);
+
+        harness.mouse_move((50.0, 50.0));
+        harness.key(Key::PageDown);
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 90.0));
+
+        harness.key(Key::End);
+        assert_eq!(scroll_offset(&mut harness), Vec2::new(0.0, 200.0));
+
+        harness.key(Key::Home);
+        assert_eq!(scroll_offset(&mut harness), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::*;
+use std::any::Any;
+
+/// Struct for the `scroll` gesture.
+#[derive(Clone)]
+pub struct ScrollGesture<V, F> {
+    child: V,
+    func: F,
+}
+
+impl<V, A, F> ScrollGesture<V, F>
+where
+    V: View,
+    A: 'static,
+    F: Fn(&mut Context, Vec2) -> A + Clone + 'static,
+{
+    pub fn new(child: V, func: F) -> Self {
+        Self { child, func }
+    }
+}
+
+impl<V, A, F> DynView for ScrollGesture<V, F>
+where
+    V: View,
+    A: 'static,
+    F: Fn(&mut Context, Vec2) -> A + Clone + 'static,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        path.push(0);
+        self.child.process(event, path, ctx, actions);
+        path.pop();
+
+        // Children go first so the innermost scrollable view gets the event.
+        if let Event::Scroll {
+            position, delta, ..
+        } = event
+        {
+            if !ctx.event_handled && self.hittest(path, *position, ctx).is_some() {
+                ctx.event_handled = true;
+                actions.push(Box::new((self.func)(ctx, *delta)));
+            }
+        }
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        path.push(0);
+        let scene = self.child.draw(path, ctx);
+        path.pop();
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_scroll_innermost() {
+        let ui = state(
+            || (Vec2::ZERO, Vec2::ZERO),
+            |s, _| {
+                zstack((
+                    rectangle(),
+                    rectangle()
+                        .sized((20.0, 20.0))
+                        .scroll(move |cx, delta| cx[s].1 += delta),
+                ))
+                .scroll(move |cx, delta| cx[s].0 += delta)
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<(Vec2, Vec2)>(&vec![0]);
+
+        harness.scroll((10.0, 10.0), (0.0, 5.0));
+        assert_eq!(harness.cx()[s], (Vec2::ZERO, Vec2::new(0.0, 5.0)));
+
+        harness.scroll((80.0, 80.0), (0.0, -3.0));
+        assert_eq!(harness.cx()[s], (Vec2::new(0.0, -3.0), Vec2::new(0.0, 5.0)));
+    }
+}
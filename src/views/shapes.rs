@@ -3,6 +3,17 @@ use crate::*;
 use peniko::Brush;
 use vello::kurbo::{Affine, RoundedRect};
 
+/// Animates changes between solid colors.
+fn animate_paint(paint: &Brush, path: &IdPath, ctx: &mut Context) -> Brush {
+    match paint {
+        Brush::Solid(color) => {
+            let id = ctx.view_id(path);
+            Brush::Solid(ctx.animate(id, "paint", *color))
+        }
+        paint => paint.clone(),
+    }
+}
+
 /// Struct for `circle`.
 #[derive(Clone)]
 pub struct Circle {
@@ -30,7 +41,7 @@ impl DynView for Circle {
         scene.fill(
             peniko::Fill::EvenOdd,
             Affine::IDENTITY,
-            &self.paint,
+            &animate_paint(&self.paint, path, ctx),
             None,
             &kurbo::Circle::new(center, radius),
         );
@@ -106,7 +117,7 @@ impl DynView for Rectangle {
         scene.fill(
             peniko::Fill::EvenOdd,
             Affine::IDENTITY,
-            &self.paint,
+            &animate_paint(&self.paint, path, ctx),
             None,
             &RoundedRect::from_rect(rect, self.corner_radius),
         );
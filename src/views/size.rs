@@ -37,10 +37,12 @@ where
     }
 
     fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        let id = args.ctx.view_id(path);
+        let size = args.ctx.animate(id, "size", self.size);
         path.push(0);
-        self.child.layout(path, &mut args.with_size(self.size));
+        self.child.layout(path, &mut args.with_size(size));
         path.pop();
-        self.size
+        size
     }
 
     fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
@@ -57,6 +59,7 @@ where
     }
 
     fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
         path.push(0);
         self.child.gc(path, ctx, map);
         path.pop();
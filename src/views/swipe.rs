@@ -0,0 +1,130 @@
+use crate::*;
+use std::any::Any;
+
+/// Shortest distance a swipe travels.
+const SWIPE_MIN_DISTANCE: f64 = 50.0;
+
+/// Longest time a swipe takes.
+const SWIPE_MAX_DURATION: f64 = 0.5;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Default)]
+pub struct SwipeState {
+    down: Option<usize>,
+    start: Point,
+
+    /// Clock time the touch went down.
+    since: f64,
+
+    direction: Option<SwipeDirection>,
+}
+
+/// Recognizes a quick flick in one direction.
+#[derive(Clone)]
+pub struct SwipeRecognizer<F> {
+    func: F,
+}
+
+impl<F> SwipeRecognizer<F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<A: 'static, F: Fn(&mut Context, SwipeDirection) -> A + Clone + 'static> Recognizer
+    for SwipeRecognizer<F>
+{
+    type State = SwipeState;
+
+    fn begin(
+        &self,
+        state: &mut SwipeState,
+        id: usize,
+        position: Point,
+        _button: Option<MouseButton>,
+        now: f64,
+    ) -> Option<Recognition> {
+        if state.down.is_some() {
+            return None;
+        }
+        state.down = Some(id);
+        state.start = position;
+        state.since = now;
+        Some(Recognition::Possible)
+    }
+
+    fn update(&self, state: &mut SwipeState, event: &Event, now: f64) -> Recognition {
+        match event {
+            Event::TouchEnd { position, .. } => {
+                let d = *position - state.start;
+                if d.hypot() < SWIPE_MIN_DISTANCE {
+                    return Recognition::Reject;
+                }
+                state.direction = Some(if d.x.abs() > d.y.abs() {
+                    if d.x > 0.0 {
+                        SwipeDirection::Right
+                    } else {
+                        SwipeDirection::Left
+                    }
+                } else if d.y > 0.0 {
+                    SwipeDirection::Down
+                } else {
+                    SwipeDirection::Up
+                });
+                Recognition::Accept
+            }
+            Event::Anim => {
+                if now - state.since > SWIPE_MAX_DURATION {
+                    Recognition::Reject
+                } else {
+                    Recognition::Possible
+                }
+            }
+            _ => Recognition::Possible,
+        }
+    }
+
+    fn recognized(&self, ctx: &mut Context, state: &SwipeState, actions: &mut Vec<Box<dyn Any>>) {
+        if let Some(direction) = state.direction {
+            actions.push(Box::new((self.func)(ctx, direction)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_swipe() {
+        let ui = state(
+            || vec![],
+            |swipes, _| {
+                rectangle()
+                    .tap(move |cx| cx[swipes].push(None))
+                    .swipe(move |cx, direction| cx[swipes].push(Some(direction)))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (200.0, 200.0));
+        let s = harness.state_handle::<Vec<Option<SwipeDirection>>>(&vec![0]);
+
+        harness.drag((150.0, 100.0), (50.0, 110.0), 3);
+        harness.drag((100.0, 50.0), (100.0, 150.0), 3);
+
+        // Too short, so it's a tap.
+        harness.drag((100.0, 100.0), (110.0, 100.0), 3);
+
+        assert_eq!(
+            harness.cx()[s],
+            vec![Some(SwipeDirection::Left), Some(SwipeDirection::Down), None]
+        );
+    }
+}
@@ -57,93 +57,200 @@ impl<A: Clone + 'static> TapFn for TapActionAdapter<A> {
     }
 }
 
-/// Struct for the `tap` gesture.
-#[derive(Clone)]
-pub struct Tap<V, F> {
-    /// Child view tree.
-    child: V,
+/// Longest wait between the taps of a multi-tap.
+const MULTI_TAP_INTERVAL: f64 = 0.3;
+
+/// How far a touch can move and still be a tap, and how far
+/// apart the taps of a multi-tap can be.
+const MULTI_TAP_SLOP: f64 = 20.0;
+
+#[derive(Default)]
+pub struct TapState {
+    /// Taps so far.
+    count: usize,
+
+    /// Touch currently down.
+    down: Option<usize>,
+
+    start: Point,
+    pt: Point,
+    button: Option<MouseButton>,
 
-    /// Called when a tap occurs.
+    /// Clock time the last tap ended.
+    last_tap: f64,
+}
+
+/// Recognizes one or more taps in a row.
+///
+/// A single tap fires when the touch ends, unless it moved too far,
+/// but waits for any multi-tap on the same view to fail first.
+#[derive(Clone)]
+pub struct TapRecognizer<F> {
+    taps: usize,
     func: F,
 }
 
-impl<V, F> Tap<V, F>
-where
-    V: View,
-    F: TapFn + 'static,
-{
-    pub fn new(v: V, f: F) -> Self {
-        Self { child: v, func: f }
+impl<F: TapFn + 'static> TapRecognizer<F> {
+    pub fn new(taps: usize, func: F) -> Self {
+        Self {
+            taps: taps.max(1),
+            func,
+        }
     }
 }
 
-impl<V, F> DynView for Tap<V, F>
-where
-    V: View,
-    F: TapFn + 'static,
-{
-    fn process(
+impl<F: TapFn + 'static> Recognizer for TapRecognizer<F> {
+    type State = TapState;
+
+    fn begin(
         &self,
-        event: &Event,
-        path: &mut IdPath,
-        ctx: &mut Context,
-        actions: &mut Vec<Box<dyn Any>>,
-    ) {
-        let vid = ctx.view_id(path);
-        match &event {
-            Event::TouchBegin { id, position } => {
-                if self.hittest(path, *position, ctx).is_some() {
-                    ctx.touches[*id] = vid;
+        state: &mut TapState,
+        id: usize,
+        position: Point,
+        button: Option<MouseButton>,
+        _now: f64,
+    ) -> Option<Recognition> {
+        if state.down.is_some() || state.count >= self.taps {
+            return None;
+        }
+        if state.count > 0 && (position - state.start).hypot() > MULTI_TAP_SLOP {
+            return Some(Recognition::Reject);
+        }
+        state.down = Some(id);
+        state.start = position;
+        state.pt = position;
+        state.button = button;
+        Some(Recognition::Possible)
+    }
+
+    fn update(&self, state: &mut TapState, event: &Event, now: f64) -> Recognition {
+        match event {
+            Event::TouchMove { position, .. } => {
+                if (*position - state.start).hypot() > MULTI_TAP_SLOP {
+                    return Recognition::Reject;
                 }
             }
-            Event::TouchEnd { id, position } => {
-                if ctx.touches[*id] == vid {
-                    ctx.touches[*id] = ViewId::default();
-                    self.func.call(
-                        ctx,
-                        TapInfo {
-                            pt: *position,
-                            button: ctx.mouse_button,
-                            state: TouchState::End,
-                        },
-                        actions,
-                    )
+            Event::TouchEnd { position, .. } => {
+                state.down = None;
+                state.count += 1;
+                state.pt = *position;
+                state.last_tap = now;
+            }
+            Event::Anim => {
+                if state.down.is_none()
+                    && state.count < self.taps
+                    && now - state.last_tap > MULTI_TAP_INTERVAL
+                {
+                    return Recognition::Reject;
                 }
             }
             _ => (),
         }
+        if state.count >= self.taps {
+            Recognition::Ready
+        } else {
+            Recognition::Possible
+        }
     }
 
-    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
-        path.push(0);
-        let scene = self.child.draw(path, ctx);
-        path.pop();
-        scene
+    fn recognized(&self, ctx: &mut Context, state: &TapState, actions: &mut Vec<Box<dyn Any>>) {
+        self.func.call(
+            ctx,
+            TapInfo {
+                pt: state.pt,
+                button: state.button,
+                state: TouchState::End,
+            },
+            actions,
+        )
     }
+}
+
+/// Struct for the `tap`, `double_tap` and `triple_tap` gestures.
+pub type Tap<V, F> = Gesture<V, TapRecognizer<F>>;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
 
-    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
-        path.push(0);
-        let size = self.child.layout(path, args);
-        path.pop();
-        size
+    fn taps_ui() -> impl View {
+        state(
+            || vec![],
+            |taps, _| {
+                rectangle()
+                    .tap(move |cx| cx[taps].push(1))
+                    .double_tap(move |cx| cx[taps].push(2))
+            },
+        )
     }
 
-    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
-        path.push(0);
-        let id = self.child.hittest(path, pt, ctx);
-        path.pop();
-        id
+    #[test]
+    fn test_tap_waits_for_double_tap() {
+        let mut harness = TestHarness::new(taps_ui(), (100.0, 100.0));
+        let s = harness.state_handle::<Vec<i32>>(&vec![0]);
+
+        harness.tap_at((50.0, 50.0));
+        assert_eq!(harness.cx()[s], vec![]);
+
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[s], vec![1]);
     }
 
-    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
-        path.push(0);
-        self.child.commands(path, ctx, cmds);
-        path.pop();
+    #[test]
+    fn test_double_tap_beats_tap() {
+        let mut harness = TestHarness::new(taps_ui(), (100.0, 100.0));
+        let s = harness.state_handle::<Vec<i32>>(&vec![0]);
+
+        harness.tap_at((50.0, 50.0));
+        harness.tap_at((52.0, 50.0));
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[s], vec![2]);
+
+        // Taps too far apart are two single taps.
+        harness.tap_at((10.0, 10.0));
+        harness.tap_at((90.0, 90.0));
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[s], vec![2, 1, 1]);
     }
 
-    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
-        path.push(0);
-        self.child.gc(path, ctx, map);
-        path.pop();
+    #[test]
+    fn test_tap_rejects_drag() {
+        let mut harness = TestHarness::new(taps_ui(), (100.0, 100.0));
+        let s = harness.state_handle::<Vec<i32>>(&vec![0]);
+
+        harness.drag((20.0, 50.0), (80.0, 50.0), 4);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[s], vec![]);
+
+        // Small movements are still taps.
+        harness.drag((50.0, 50.0), (55.0, 50.0), 1);
+        harness.advance(0.5);
+        assert_eq!(harness.cx()[s], vec![1]);
+    }
+
+    #[test]
+    fn test_triple_tap() {
+        let ui = state(
+            || vec![],
+            |taps, _| {
+                rectangle()
+                    .double_tap(move |cx| cx[taps].push(2))
+                    .triple_tap(move |cx| cx[taps].push(3))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (100.0, 100.0));
+        let s = harness.state_handle::<Vec<i32>>(&vec![0]);
+
+        for _ in 0..3 {
+            harness.tap_at((50.0, 50.0));
+        }
+        harness.advance(0.5);
+        for _ in 0..2 {
+            harness.tap_at((50.0, 50.0));
+        }
+        harness.advance(0.5);
+
+        assert_eq!(harness.cx()[s], vec![3, 2]);
     }
 }
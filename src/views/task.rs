@@ -0,0 +1,94 @@
+use crate::*;
+use std::any::Any;
+use std::future::Future;
+
+/// Struct for the `task` modifier.
+#[derive(Clone)]
+pub struct TaskView<V, M, F> {
+    child: V,
+    make_future: M,
+    func: F,
+}
+
+impl<V, M, Fut, F> TaskView<V, M, F>
+where
+    V: View,
+    M: Fn() -> Fut + Clone + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+    F: Fn(&mut Context, Fut::Output) + Clone + 'static,
+{
+    pub fn new(child: V, make_future: M, func: F) -> Self {
+        Self {
+            child,
+            make_future,
+            func,
+        }
+    }
+}
+
+impl<V, M, Fut, F> DynView for TaskView<V, M, F>
+where
+    V: View,
+    M: Fn() -> Fut + Clone + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+    F: Fn(&mut Context, Fut::Output) + Clone + 'static,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        // Start the task the first time we see the view. The flag is
+        // garbage collected with the view, so it restarts if the view
+        // goes away and comes back.
+        let id = ctx.view_id(path);
+        ctx.init_state(id, &|| false);
+        if !ctx[StateHandle::<bool>::new(id)] {
+            ctx.set_state(id, true);
+            let func = self.func.clone();
+            ctx.spawn_task(id, (self.make_future)(), move |cx, value| func(cx, value));
+        }
+
+        path.push(0);
+        self.child.process(event, path, ctx, actions);
+        path.pop();
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        path.push(0);
+        let scene = self.child.draw(path, ctx);
+        path.pop();
+        scene
+    }
+
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        path.push(0);
+        let size = self.child.layout(path, args);
+        path.pop();
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        path.push(0);
+        let id = self.child.hittest(path, pt, ctx);
+        path.pop();
+        id
+    }
+
+    fn commands(&self, path: &mut IdPath, ctx: &mut Context, cmds: &mut Vec<CommandInfo>) {
+        path.push(0);
+        self.child.commands(path, ctx, cmds);
+        path.pop();
+    }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+        path.push(0);
+        self.child.gc(path, ctx, map);
+        path.pop();
+    }
+}
@@ -1,8 +1,11 @@
 use parley::{
-    AlignmentOptions, FontContext, GenericFamily, Layout, LayoutContext, PositionedLayoutItem,
-    StyleProperty,
+    Affinity, AlignmentOptions, Cursor, FontContext, FontStack, FontStyle, FontWeight,
+    GenericFamily, Layout, LayoutContext, PositionedLayoutItem, Selection, StyleProperty,
 };
-use vello::kurbo::Affine;
+use std::any::Any;
+use std::borrow::Cow;
+use std::ops::Range;
+use vello::kurbo::{Affine, Line, Stroke};
 
 use crate::*;
 
@@ -10,145 +13,565 @@ pub trait TextModifiers: View + Sized {
     fn font_size(self, size: f32) -> Text;
     fn color(self, color: Color) -> Text;
     fn max_width(self, max_width: f32) -> Text;
+    fn text_align(self, align: TextAlign) -> Text;
+    fn line_height(self, line_height: f32) -> Text;
+    fn max_lines(self, max_lines: usize) -> Text;
+    fn truncation(self, truncation: Truncation) -> Text;
+    fn font_family(self, family: &str) -> Text;
 }
 
-/// Struct for `text`.
+/// Font family for text which doesn't choose one with `font_family`.
+/// Set it for part of the UI with the `env` modifier, after adding the
+/// font with `Context::register_font`.
+///
+/// ```no_run
+/// # use vui::*;
+/// vstack(("Hello", "world")).env(DefaultFont::family("Inter"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DefaultFont(pub Option<String>);
+
+impl DefaultFont {
+    pub fn family(family: &str) -> Self {
+        Self(Some(family.into()))
+    }
+}
+
+/// How the lines of a paragraph line up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Spread words out to fill every line but the last.
+    Justify,
+}
+
+/// Which part of the text is replaced by an ellipsis when
+/// it doesn't fit in `max_lines`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Truncation {
+    #[default]
+    Tail,
+    Middle,
+    Head,
+}
+
+/// Options for laying out a paragraph.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ParagraphStyle {
+    pub(crate) align: TextAlign,
+    /// Multiple of the font size.
+    pub(crate) line_height: f32,
+    pub(crate) max_lines: Option<usize>,
+    pub(crate) truncation: Truncation,
+    /// Font family names in CSS format, or `None` for the system font.
+    pub(crate) family: Option<String>,
+}
+
+impl Default for ParagraphStyle {
+    fn default() -> Self {
+        Self {
+            align: TextAlign::Start,
+            line_height: 1.2,
+            max_lines: None,
+            truncation: Truncation::Tail,
+            family: None,
+        }
+    }
+}
+
+/// Struct for `text` and `rich_text`.
 #[derive(Clone)]
 pub struct Text {
     text: String,
+    spans: Vec<TextSpan>,
     size: f32,
     color: Color,
     max_width: Option<f32>,
+    paragraph: ParagraphStyle,
+    selectable: bool,
+}
+
+/// Selection state for `Text::selectable`.
+#[derive(Default)]
+struct TextSelectionState {
+    selection: Selection,
+
+    /// Clicks in a row, and the clock time of the last one.
+    clicks: usize,
+    last_click: f64,
+}
+
+/// What a `Text` was laid out from.
+#[derive(Clone, PartialEq)]
+struct TextLayoutKey {
+    text: String,
+    spans: Vec<TextSpan>,
+    size: f32,
+    width: Option<f32>,
+    color: Color,
+    paragraph: ParagraphStyle,
+}
+
+/// The last layout of a `Text`, so truncated text isn't searched
+/// for again on every layout and draw.
+struct TextLayoutCache {
+    key: TextLayoutKey,
+    layout: Layout<ColorBrush>,
+
+    /// The text with an ellipsis, if it was truncated.
+    truncated: Option<String>,
+}
+
+#[derive(Default)]
+struct TextState {
+    selection: TextSelectionState,
+    cache: Option<TextLayoutCache>,
 }
 
 impl Text {
     pub const DEFAULT_SIZE: f32 = 14.0;
     pub fn color(self, color: Color) -> Text {
+        Text { color, ..self }
+    }
+
+    /// Lets the user select the text with the mouse, and copy it
+    /// with the usual shortcut.
+    pub fn selectable(self) -> Text {
         Text {
-            text: self.text,
-            size: self.size,
-            color,
-            max_width: self.max_width,
+            selectable: true,
+            ..self
         }
     }
-}
 
-impl DynView for Text {
-    fn draw(&self, _path: &mut IdPath, ctx: &mut Context) -> Scene {
-        draw_text(
+    /// Lays out the text at the width from the last layout. Also
+    /// returns the text, which is shortened if it was truncated.
+    fn build<'a>(&'a self, path: &IdPath, ctx: &mut Context) -> (Layout<ColorBrush>, Cow<'a, str>) {
+        let vid = ctx.view_id(path);
+        ctx.init_state(vid, &TextState::default);
+        let width = match &ctx.state_mut::<TextState>(vid).cache {
+            Some(cache) => cache.key.width,
+            None => self.max_width,
+        };
+        self.build_at(path, ctx, width)
+    }
+
+    /// Lays out the text at `width`, reusing the last layout
+    /// if nothing it depends on has changed.
+    fn build_at<'a>(
+        &'a self,
+        path: &IdPath,
+        ctx: &mut Context,
+        width: Option<f32>,
+    ) -> (Layout<ColorBrush>, Cow<'a, str>) {
+        let vid = ctx.view_id(path);
+        ctx.init_state(vid, &TextState::default);
+        let key = TextLayoutKey {
+            text: self.text.clone(),
+            spans: self.spans.clone(),
+            size: self.size,
+            width,
+            color: self.color,
+            paragraph: self.paragraph(ctx),
+        };
+
+        let cache = &ctx.state_mut::<TextState>(vid).cache;
+        if let Some(cache) = cache.as_ref().filter(|cache| cache.key == key) {
+            let text = match &cache.truncated {
+                Some(truncated) => Cow::Owned(truncated.clone()),
+                None => Cow::Borrowed(self.text.as_str()),
+            };
+            return (cache.layout.clone(), text);
+        }
+
+        let (layout, text) = build_layout_text(
             &self.text,
+            &self.spans,
             self.size,
-            Vec2::ZERO,
-            self.max_width,
-            TEXT_COLOR,
+            width,
+            Some(self.color),
+            &key.paragraph,
             &mut ctx.font_ctx,
-        )
+        );
+        ctx.state_mut::<TextState>(vid).cache = Some(TextLayoutCache {
+            key,
+            layout: layout.clone(),
+            truncated: match &text {
+                Cow::Owned(truncated) => Some(truncated.clone()),
+                Cow::Borrowed(_) => None,
+            },
+        });
+        (layout, text)
+    }
+
+    /// Our paragraph options, taking the font from `DefaultFont`
+    /// unless we have our own.
+    fn paragraph(&self, ctx: &mut Context) -> ParagraphStyle {
+        let mut paragraph = self.paragraph.clone();
+        if paragraph.family.is_none() {
+            paragraph.family = ctx.init_env(&DefaultFont::default).0;
+        }
+        paragraph
+    }
+}
+
+impl DynView for Text {
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        _actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        if !self.selectable {
+            return;
+        }
+        let vid = ctx.view_id(path);
+        let rect = ctx.get_layout(path).rect;
+        ctx.init_state(vid, &TextState::default);
+        let mut s = std::mem::take(&mut ctx.state_mut::<TextState>(vid).selection);
+        let focused = ctx.focused_id == Some(vid);
+        let selection = s.selection;
+
+        match event {
+            Event::TouchBegin { id, position } => {
+                if rect.contains(*position) {
+                    ctx.focused_id = Some(vid);
+                    ctx.capture_touch(*id, vid);
+                    let now = ctx.now();
+                    s.clicks = if now - s.last_click < MULTI_CLICK_INTERVAL {
+                        s.clicks + 1
+                    } else {
+                        1
+                    };
+                    s.last_click = now;
+
+                    let (layout, _) = self.build(path, ctx);
+                    let (x, y) = (position.x as f32, position.y as f32);
+                    s.selection = match s.clicks {
+                        1 if ctx.key_mods.shift && focused => {
+                            s.selection.extend_to_point(&layout, x, y)
+                        }
+                        1 => Selection::from_point(&layout, x, y),
+                        2 => Selection::word_from_point(&layout, x, y),
+                        _ => Selection::line_from_point(&layout, x, y),
+                    };
+                } else if focused {
+                    // Clicking elsewhere deselects.
+                    ctx.focused_id = None;
+                    s.selection = s.selection.collapse();
+                }
+            }
+            Event::TouchMove { id, position, .. } if ctx.has_touch(*id, vid) => {
+                let (layout, _) = self.build(path, ctx);
+                s.selection =
+                    s.selection
+                        .extend_to_point(&layout, position.x as f32, position.y as f32);
+            }
+            // The mouse is touch 0.
+            Event::PointerMove { position, .. } if ctx.has_touch(0, vid) => {
+                let (layout, _) = self.build(path, ctx);
+                s.selection =
+                    s.selection
+                        .extend_to_point(&layout, position.x as f32, position.y as f32);
+            }
+            Event::TouchEnd { id, .. } => {
+                ctx.release_touch(*id, vid);
+            }
+            Event::Key(Key::Character(c)) if focused && shortcut_modifier(&ctx.key_mods) => {
+                let (layout, text) = self.build(path, ctx);
+                match c.to_ascii_lowercase() {
+                    'c' if !s.selection.is_collapsed() => {
+                        let selected = text[s.selection.text_range()].to_string();
+                        ctx.clipboard_set_text(selected);
+                    }
+                    'a' => {
+                        s.selection = Selection::new(
+                            Cursor::from_byte_index(&layout, 0, Affinity::Downstream),
+                            Cursor::from_byte_index(&layout, text.len(), Affinity::Upstream),
+                        );
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+
+        if s.selection.text_range() != selection.text_range()
+            || focused != (ctx.focused_id == Some(vid))
+        {
+            ctx.set_dirty();
+        }
+        ctx.state_mut::<TextState>(vid).selection = s;
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        // Wrap and align to the width we were laid out in.
+        let (layout, _) = self.build(path, ctx);
+        let mut scene = Scene::new();
+
+        if self.selectable {
+            let vid = ctx.view_id(path);
+            if ctx.focused_id == Some(vid) {
+                let selection = ctx.state_mut::<TextState>(vid).selection.selection;
+                for rect in selection.geometry(&layout) {
+                    scene.fill(
+                        peniko::Fill::NonZero,
+                        Affine::IDENTITY,
+                        selection_color(),
+                        None,
+                        &rect,
+                    );
+                }
+            }
+        }
+
+        scene.append(&draw_layout(&layout, Affine::IDENTITY), None);
+        scene
     }
-    fn layout(&self, _path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
         let width = match self.max_width {
             None => args.size.width as _,
             Some(max_width) => max_width.min(args.size.width as _),
         };
-        get_text_bounds(
-            &self.text,
-            self.size,
-            Some(width as _),
-            &mut args.ctx.font_ctx,
-        )
+        let (layout, _) = self.build_at(path, args.ctx, Some(width));
+        let height = layout.height() as f64;
+
+        // Aligned text fills the width so its lines have room to move.
+        let size = if self.paragraph.align != TextAlign::Start && width.is_finite() {
+            Size::new(width as f64, height)
+        } else {
+            Size::new(layout.full_width() as f64, height)
+        };
+
+        let offset = args.ctx.get_layout(path).offset;
+        args.ctx.update_layout(
+            path,
+            LayoutBox {
+                rect: Rect::from_origin_size(Point::ZERO, size),
+                offset,
+            },
+        );
+        size
+    }
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        if self.selectable && ctx.get_layout(path).rect.contains(pt) {
+            Some(ctx.view_id(path))
+        } else {
+            None
+        }
     }
-    fn hittest(&self, _path: &mut IdPath, _pt: Point, _ctx: &mut Context) -> Option<ViewId> {
-        None
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
     }
 }
 
+/// Background of selected text.
+pub(crate) fn selection_color() -> Color {
+    AZURE_HIGHLIGHT_DARK.multiply_alpha(0.5)
+}
+
 impl TextModifiers for Text {
     fn font_size(self, size: f32) -> Self {
-        Self {
-            text: self.text,
-            color: self.color,
-            size,
-            max_width: self.max_width,
-        }
+        Self { size, ..self }
     }
     fn color(self, color: Color) -> Text {
-        Text {
-            text: self.text,
-            size: self.size,
-            color,
-            max_width: self.max_width,
-        }
+        Text { color, ..self }
     }
     fn max_width(self, max_width: f32) -> Text {
         Text {
-            text: self.text,
-            size: self.size,
-            color: self.color,
             max_width: Some(max_width),
+            ..self
         }
     }
+    fn text_align(self, align: TextAlign) -> Text {
+        let paragraph = ParagraphStyle {
+            align,
+            ..self.paragraph
+        };
+        Text { paragraph, ..self }
+    }
+    fn line_height(self, line_height: f32) -> Text {
+        let paragraph = ParagraphStyle {
+            line_height,
+            ..self.paragraph
+        };
+        Text { paragraph, ..self }
+    }
+    fn max_lines(self, max_lines: usize) -> Text {
+        let paragraph = ParagraphStyle {
+            max_lines: Some(max_lines),
+            ..self.paragraph
+        };
+        Text { paragraph, ..self }
+    }
+    fn truncation(self, truncation: Truncation) -> Text {
+        let paragraph = ParagraphStyle {
+            truncation,
+            ..self.paragraph
+        };
+        Text { paragraph, ..self }
+    }
+    fn font_family(self, family: &str) -> Text {
+        let paragraph = ParagraphStyle {
+            family: Some(family.into()),
+            ..self.paragraph
+        };
+        Text { paragraph, ..self }
+    }
 }
 
 /// Shows a string as a label (not editable).
 pub fn text(name: &str) -> Text {
     Text {
         text: String::from(name),
+        spans: vec![],
         size: Text::DEFAULT_SIZE,
         color: TEXT_COLOR,
         max_width: None,
+        paragraph: ParagraphStyle::default(),
+        selectable: false,
+    }
+}
+
+/// A run of text within a `rich_text`, with its own style.
+///
+/// Anything not set on the span comes from the `rich_text`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSpan {
+    text: String,
+    family: Option<String>,
+    weight: Option<f32>,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    letter_spacing: Option<f32>,
+    color: Option<Color>,
+}
+
+impl TextSpan {
+    /// Font family names in CSS format, like `"Inter, sans-serif"`.
+    pub fn font_family(self, family: &str) -> Self {
+        Self {
+            family: Some(family.into()),
+            ..self
+        }
+    }
+
+    /// Font weight, from 1 to 1000. Normal is 400.
+    pub fn weight(self, weight: f32) -> Self {
+        Self {
+            weight: Some(weight),
+            ..self
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD.value())
+    }
+
+    pub fn italic(self) -> Self {
+        Self {
+            italic: true,
+            ..self
+        }
+    }
+
+    pub fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    pub fn strikethrough(self) -> Self {
+        Self {
+            strikethrough: true,
+            ..self
+        }
+    }
+
+    /// Extra space between letters, in points.
+    pub fn letter_spacing(self, spacing: f32) -> Self {
+        Self {
+            letter_spacing: Some(spacing),
+            ..self
+        }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+}
+
+/// Creates a span for `rich_text`.
+pub fn span(text: &str) -> TextSpan {
+    TextSpan {
+        text: text.into(),
+        ..Default::default()
+    }
+}
+
+/// Shows a paragraph made of differently styled spans (not editable).
+///
+/// ```no_run
+/// # use vui::*;
+/// rich_text([span("Hello, "), span("world").bold().color(AZURE_HIGHLIGHT)]);
+/// ```
+pub fn rich_text(spans: impl IntoIterator<Item = TextSpan>) -> Text {
+    let spans: Vec<TextSpan> = spans.into_iter().collect();
+    Text {
+        text: spans.iter().map(|span| span.text.as_str()).collect(),
+        spans,
+        ..text("")
     }
 }
 
 macro_rules! impl_text {
     ( $ty:ident ) => {
         impl DynView for $ty {
-            fn draw(&self, _path: &mut IdPath, ctx: &mut Context) -> Scene {
-                let text = &format!("{}", self);
-                draw_text(
-                    text,
-                    Text::DEFAULT_SIZE,
-                    Vec2::ZERO,
-                    None,
-                    TEXT_COLOR,
-                    &mut ctx.font_ctx,
-                )
+            fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+                text(&format!("{}", self)).draw(path, ctx)
             }
-            fn layout(&self, _path: &mut IdPath, args: &mut LayoutArgs) -> Size {
-                let text = &format!("{}", self);
-                let width = args.size.width;
-                get_text_bounds(
-                    text,
-                    Text::DEFAULT_SIZE,
-                    Some(width as _),
-                    &mut args.ctx.font_ctx,
-                )
+            fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+                text(&format!("{}", self)).layout(path, args)
+            }
+            fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+                text(&format!("{}", self)).gc(path, ctx, map)
             }
         }
 
         impl TextModifiers for $ty {
             fn font_size(self, size: f32) -> Text {
-                Text {
-                    text: format!("{}", self),
-                    size,
-                    color: TEXT_COLOR,
-                    max_width: None,
-                }
+                text(&format!("{}", self)).font_size(size)
             }
             fn color(self, color: Color) -> Text {
-                Text {
-                    text: format!("{}", self),
-                    size: Text::DEFAULT_SIZE,
-                    color,
-                    max_width: None,
-                }
+                text(&format!("{}", self)).color(color)
             }
 
             fn max_width(self, max_width: f32) -> Text {
-                Text {
-                    text: format!("{}", self),
-                    size: Text::DEFAULT_SIZE,
-                    color: TEXT_COLOR,
-                    max_width: Some(max_width),
-                }
+                text(&format!("{}", self)).max_width(max_width)
+            }
+            fn text_align(self, align: TextAlign) -> Text {
+                text(&format!("{}", self)).text_align(align)
+            }
+            fn line_height(self, line_height: f32) -> Text {
+                text(&format!("{}", self)).line_height(line_height)
+            }
+            fn max_lines(self, max_lines: usize) -> Text {
+                text(&format!("{}", self)).max_lines(max_lines)
+            }
+            fn truncation(self, truncation: Truncation) -> Text {
+                text(&format!("{}", self)).truncation(truncation)
+            }
+            fn font_family(self, family: &str) -> Text {
+                text(&format!("{}", self)).font_family(family)
             }
         }
     };
@@ -167,57 +590,47 @@ impl_text!(f64);
 
 // XXX: Can't do impl_text!(&'static str)
 impl DynView for &'static str {
-    fn draw(&self, _path: &mut IdPath, ctx: &mut Context) -> Scene {
-        draw_text(
-            self,
-            Text::DEFAULT_SIZE,
-            Vec2::ZERO,
-            None,
-            TEXT_COLOR,
-            &mut ctx.font_ctx,
-        )
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        text(self).draw(path, ctx)
     }
-    fn layout(&self, _path: &mut IdPath, args: &mut LayoutArgs) -> Size {
-        let width = args.size.width;
-        get_text_bounds(
-            self,
-            Text::DEFAULT_SIZE,
-            Some(width as _),
-            &mut args.ctx.font_ctx,
-        )
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        text(self).layout(path, args)
+    }
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        text(self).gc(path, ctx, map)
     }
 }
 
 impl TextModifiers for &'static str {
     fn font_size(self, size: f32) -> Text {
-        Text {
-            text: format!("{}", self),
-            size,
-            color: TEXT_COLOR,
-            max_width: None,
-        }
+        text(self).font_size(size)
     }
     fn color(self, color: Color) -> Text {
-        Text {
-            text: format!("{}", self),
-            size: Text::DEFAULT_SIZE,
-            color,
-            max_width: None,
-        }
+        text(self).color(color)
     }
     fn max_width(self, max_width: f32) -> Text {
-        Text {
-            text: format!("{}", self),
-            size: Text::DEFAULT_SIZE,
-            color: TEXT_COLOR,
-            max_width: Some(max_width),
-        }
+        text(self).max_width(max_width)
+    }
+    fn text_align(self, align: TextAlign) -> Text {
+        text(self).text_align(align)
+    }
+    fn line_height(self, line_height: f32) -> Text {
+        text(self).line_height(line_height)
+    }
+    fn max_lines(self, max_lines: usize) -> Text {
+        text(self).max_lines(max_lines)
+    }
+    fn truncation(self, truncation: Truncation) -> Text {
+        text(self).truncation(truncation)
+    }
+    fn font_family(self, family: &str) -> Text {
+        text(self).font_family(family)
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-struct ColorBrush {
-    color: Color,
+pub(crate) struct ColorBrush {
+    pub(crate) color: Color,
 }
 
 impl Default for ColorBrush {
@@ -230,43 +643,192 @@ impl Default for ColorBrush {
 
 fn build_layout(
     text: &str,
+    spans: &[TextSpan],
     font_size: f32,
     max_width: Option<f32>,
     color: Option<Color>,
+    paragraph: &ParagraphStyle,
+    font_ctx: &mut FontContext,
+) -> Layout<ColorBrush> {
+    build_layout_text(
+        text, spans, font_size, max_width, color, paragraph, font_ctx,
+    )
+    .0
+}
+
+/// Like `build_layout`, but also returns the text which was laid
+/// out, which differs from `text` when it's truncated.
+fn build_layout_text<'a>(
+    text: &'a str,
+    spans: &[TextSpan],
+    font_size: f32,
+    max_width: Option<f32>,
+    color: Option<Color>,
+    paragraph: &ParagraphStyle,
+    font_ctx: &mut FontContext,
+) -> (Layout<ColorBrush>, Cow<'a, str>) {
+    let mut layout_paragraph = |text: &str, spans: &[TextSpan]| {
+        layout_spans(
+            text, spans, font_size, max_width, color, paragraph, font_ctx,
+        )
+    };
+
+    let layout = layout_paragraph(text, spans);
+    let Some(max_lines) = paragraph.max_lines.map(|n| n.max(1)) else {
+        return (layout, Cow::Borrowed(text));
+    };
+    if layout.len() <= max_lines {
+        return (layout, Cow::Borrowed(text));
+    }
+
+    // Plain text is one unstyled span.
+    let whole = [span(text)];
+    let spans = if spans.is_empty() { &whole[..] } else { spans };
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .collect();
+    let chars = bounds.len() - 1;
+
+    let truncated = |kept: usize| {
+        let (prefix, suffix) = match paragraph.truncation {
+            Truncation::Tail => (kept, 0),
+            Truncation::Middle => (kept - kept / 2, kept / 2),
+            Truncation::Head => (0, kept),
+        };
+        truncate_spans(spans, bounds[prefix]..bounds[chars - suffix])
+    };
+
+    // Find the most characters which fit alongside the ellipsis.
+    let (mut lo, mut hi) = (0, chars - 1);
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        let spans = truncated(mid);
+        let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+        if layout_paragraph(&text, &spans).len() <= max_lines {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    let spans = truncated(lo);
+    let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+    (layout_paragraph(&text, &spans), Cow::Owned(text))
+}
+
+/// Replaces the byte range `cut` of the text made by `spans` with an
+/// ellipsis, which takes the style of the text before it.
+fn truncate_spans(spans: &[TextSpan], cut: Range<usize>) -> Vec<TextSpan> {
+    let mut result = vec![];
+    let mut ellipsis = None;
+    let mut start = 0;
+    for span in spans {
+        let end = start + span.text.len();
+        for range in [start..cut.start.min(end), cut.end.max(start)..end] {
+            if !range.is_empty() {
+                result.push(TextSpan {
+                    text: span.text[range.start - start..range.end - start].into(),
+                    ..span.clone()
+                });
+            }
+            if range.end == cut.start && ellipsis.is_none() && !range.is_empty() {
+                ellipsis = Some(result.len());
+            }
+        }
+        start = end;
+    }
+
+    // With nothing before the cut, the ellipsis leads.
+    let index = ellipsis.unwrap_or(0);
+    let style = if index > 0 {
+        &result[index - 1]
+    } else {
+        result.first().unwrap_or(&spans[0])
+    };
+    result.insert(
+        index,
+        TextSpan {
+            text: "\u{2026}".into(),
+            ..style.clone()
+        },
+    );
+    result
+}
+
+fn layout_spans(
+    text: &str,
+    spans: &[TextSpan],
+    font_size: f32,
+    max_width: Option<f32>,
+    color: Option<Color>,
+    paragraph: &ParagraphStyle,
     font_ctx: &mut FontContext,
 ) -> Layout<ColorBrush> {
     let mut layout_ctx = LayoutContext::new();
     // todo: make everything scalable
     let mut builder = layout_ctx.ranged_builder(font_ctx, text, 1.0);
-    // todo: add font system
-    builder.push_default(GenericFamily::SystemUi);
+    match &paragraph.family {
+        // Fall back to the system font for characters the family doesn't have.
+        Some(family) => builder.push_default(FontStack::Source(Cow::Owned(format!(
+            "{family}, system-ui"
+        )))),
+        None => builder.push_default(GenericFamily::SystemUi),
+    }
     builder.push_default(StyleProperty::FontSize(font_size));
-    builder.push_default(StyleProperty::LineHeight(1.2));
+    builder.push_default(StyleProperty::LineHeight(paragraph.line_height));
     if let Some(color) = color {
         builder.push_default(StyleProperty::Brush(ColorBrush { color }));
     }
+
+    let mut start = 0;
+    for span in spans {
+        let range = start..start + span.text.len();
+        start = range.end;
+        if let Some(family) = &span.family {
+            builder.push(
+                FontStack::Source(Cow::Borrowed(family.as_str())),
+                range.clone(),
+            );
+        }
+        if let Some(weight) = span.weight {
+            builder.push(
+                StyleProperty::FontWeight(FontWeight::new(weight)),
+                range.clone(),
+            );
+        }
+        if span.italic {
+            builder.push(StyleProperty::FontStyle(FontStyle::Italic), range.clone());
+        }
+        if span.underline {
+            builder.push(StyleProperty::Underline(true), range.clone());
+        }
+        if span.strikethrough {
+            builder.push(StyleProperty::Strikethrough(true), range.clone());
+        }
+        if let Some(spacing) = span.letter_spacing {
+            builder.push(StyleProperty::LetterSpacing(spacing), range.clone());
+        }
+        if let Some(color) = span.color {
+            builder.push(StyleProperty::Brush(ColorBrush { color }), range);
+        }
+    }
+
     let mut layout: Layout<ColorBrush> = builder.build(text);
     layout.break_all_lines(max_width);
-    layout.align(
-        max_width,
-        parley::Alignment::Start,
-        AlignmentOptions::default(),
-    );
+    let alignment = match paragraph.align {
+        TextAlign::Start => parley::Alignment::Start,
+        TextAlign::Center => parley::Alignment::Middle,
+        TextAlign::End => parley::Alignment::End,
+        TextAlign::Justify => parley::Alignment::Justified,
+    };
+    layout.align(max_width, alignment, AlignmentOptions::default());
 
     layout
 }
 
-fn get_text_bounds(
-    text: &str,
-    font_size: f32,
-    max_width: Option<f32>,
-    font_ctx: &mut FontContext,
-) -> Size {
-    let layout = build_layout(text, font_size, max_width, None, font_ctx);
-    (layout.full_width() as f64, layout.height() as f64).into()
-}
-
-fn draw_text(
+pub(crate) fn draw_text(
     text: &str,
     font_size: f32,
     offset: Vec2,
@@ -274,9 +836,23 @@ fn draw_text(
     color: Color,
     font_ctx: &mut FontContext,
 ) -> Scene {
+    let paragraph = ParagraphStyle::default();
+    let layout = build_layout(
+        text,
+        &[],
+        font_size,
+        max_width,
+        Some(color),
+        &paragraph,
+        font_ctx,
+    );
+    draw_layout(&layout, Affine::translate(offset))
+}
+
+/// Draws the glyphs of a paragraph which has been laid out,
+/// along with underlines and strikethroughs.
+pub(crate) fn draw_layout(layout: &Layout<ColorBrush>, transform: Affine) -> Scene {
     let mut scene = Scene::new();
-    let layout = build_layout(text, font_size, max_width, Some(color), font_ctx);
-    let transform = Affine::translate(offset);
 
     for line in layout.lines() {
         for item in line.items() {
@@ -314,8 +890,181 @@ fn draw_text(
                         }
                     }),
                 );
+
+            let metrics = run.metrics();
+            let decorations = [
+                (
+                    &style.underline,
+                    metrics.underline_offset,
+                    metrics.underline_size,
+                ),
+                (
+                    &style.strikethrough,
+                    metrics.strikethrough_offset,
+                    metrics.strikethrough_size,
+                ),
+            ];
+            for (decoration, default_offset, default_size) in decorations {
+                let Some(decoration) = decoration else {
+                    continue;
+                };
+                let offset = decoration.offset.unwrap_or(default_offset);
+                let size = decoration.size.unwrap_or(default_size);
+                // The offset is to the top of the line, measured up from the baseline.
+                let y = (glyph_run.baseline() - offset + size / 2.0) as f64;
+                let x0 = glyph_run.offset() as f64;
+                let x1 = x0 + glyph_run.advance() as f64;
+                scene.stroke(
+                    &Stroke::new(size as f64),
+                    transform,
+                    decoration.brush.color,
+                    None,
+                    &Line::new((x0, y), (x1, y)),
+                );
+            }
         }
     }
 
     scene
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rich_text_matches_plain_text() {
+        let spans = rich_text([span("Hello, ").italic(), span("world").bold()]);
+        assert_eq!(spans.text, "Hello, world");
+
+        // Plain spans lay out the same as the text they make up.
+        let plain = rich_text([span("Hello, "), span("world")]);
+        let mut harness = TestHarness::new(plain, (200.0, 100.0));
+        let mut other = TestHarness::new(text("Hello, world"), (200.0, 100.0));
+        assert_eq!(harness.layout(), other.layout());
+    }
+
+    #[test]
+    fn test_default_font() {
+        let mut cx = Context::new();
+        let family = |text: Text, cx: &mut Context| text.paragraph(cx).family;
+
+        let old = cx.set_env(&DefaultFont::family("Inter"));
+        assert_eq!(family(text("a"), &mut cx).as_deref(), Some("Inter"));
+        assert_eq!(
+            family(text("a").font_family("Mono"), &mut cx).as_deref(),
+            Some("Mono")
+        );
+
+        // Restoring doesn't leave the font behind for other views.
+        cx.restore_env(old);
+        assert_eq!(family(text("a"), &mut cx), None);
+    }
+
+    #[test]
+    fn test_register_font_ignores_bad_data() {
+        let mut cx = Context::new();
+        assert!(cx.register_font(vec![0; 16]).is_empty());
+    }
+
+    #[test]
+    fn test_selectable_text() {
+        let ui = vstack((text("hello world").selectable(), text("not selectable")));
+        let mut harness = TestHarness::new(ui, (200.0, 100.0));
+        let shortcut = |harness: &mut TestHarness<_>, c| {
+            let mut mods = KeyboardModifiers::default();
+            if cfg!(target_os = "macos") {
+                mods.command = true;
+            } else {
+                mods.control = true;
+            }
+            harness.cx_mut().key_mods = mods;
+            harness.key(Key::Character(c));
+            harness.cx_mut().key_mods = KeyboardModifiers::default();
+        };
+        let label = harness.find_view_rect(&vec![0, 0]).unwrap();
+        let other = harness.find_view_rect(&vec![0, 1]).unwrap();
+
+        // Double-click selects a word.
+        harness.tap_at((label.x0 + 5.0, label.center().y));
+        harness.tap_at((label.x0 + 5.0, label.center().y));
+        shortcut(&mut harness, 'c');
+        assert_eq!(
+            harness.cx().clipboard_get_text(),
+            Some(ClipboardText::from("hello"))
+        );
+
+        shortcut(&mut harness, 'a');
+        shortcut(&mut harness, 'c');
+        assert_eq!(
+            harness.cx().clipboard_get_text(),
+            Some(ClipboardText::from("hello world"))
+        );
+
+        // Plain text doesn't take the focus.
+        harness.cx_mut().clipboard_set_text("");
+        harness.tap_at(other.center());
+        shortcut(&mut harness, 'a');
+        shortcut(&mut harness, 'c');
+        assert_eq!(
+            harness.cx().clipboard_get_text(),
+            Some(ClipboardText::from(""))
+        );
+    }
+
+    #[test]
+    fn test_truncate_spans() {
+        let spans = [span("abc").bold(), span("def")];
+        let truncated = |cut| {
+            truncate_spans(&spans, cut)
+                .iter()
+                .map(|span| span.text.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(truncated(2..6), ["ab", "\u{2026}"]);
+        assert_eq!(truncated(0..4), ["\u{2026}", "ef"]);
+        assert_eq!(truncated(1..5), ["a", "\u{2026}", "f"]);
+        assert_eq!(truncated(4..6), ["abc", "d", "\u{2026}"]);
+
+        // The ellipsis is styled like the text it follows.
+        assert_eq!(truncate_spans(&spans, 1..5)[1], span("\u{2026}").bold());
+        assert_eq!(truncate_spans(&spans, 0..4)[0], span("\u{2026}"));
+    }
+
+    #[test]
+    fn test_max_lines() {
+        let lorem = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod";
+        let mut two_lines = TestHarness::new(text("Lorem\nipsum"), (100.0, 400.0));
+        let mut truncated = TestHarness::new(text(lorem).max_lines(2), (100.0, 400.0));
+        let mut wrapped = TestHarness::new(text(lorem), (100.0, 400.0));
+
+        assert_eq!(truncated.layout().height, two_lines.layout().height);
+        assert!(wrapped.layout().height >= truncated.layout().height);
+    }
+
+    #[test]
+    fn test_truncation_is_cached() {
+        let lorem = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod";
+        let mut harness = TestHarness::new(text(lorem).max_lines(2), (100.0, 400.0));
+        harness.render();
+
+        let id = harness.view_id(&vec![0]);
+        let cache = harness.cx()[StateHandle::<TextState>::new(id)]
+            .cache
+            .as_ref()
+            .unwrap();
+        assert_eq!(cache.key.width, Some(100.0));
+        assert!(cache.truncated.as_ref().unwrap().ends_with('\u{2026}'));
+    }
+
+    #[test]
+    fn test_unconstrained_text_records_its_size() {
+        let mut harness = TestHarness::new(hscroll(text("hello")), (200.0, 100.0));
+        let size = harness.layout();
+        let rect = harness.find_view_rect(&vec![0, 0]).unwrap();
+        assert!(rect.width().is_finite() && rect.width() > 0.0);
+        assert!(rect.width() < size.width);
+    }
+}
@@ -1,175 +1,736 @@
 use crate::*;
+use parley::{
+    FontStack, GenericFamily, LayoutContext, PlainEditor, PlainEditorDriver, StyleProperty,
+};
+use std::any::Any;
+use std::borrow::Cow;
+use vello::kurbo::Affine;
 
-/// View-model for `text_editor`.
+/// Time for the caret to blink off and on again.
+const CARET_BLINK_PERIOD: f64 = 1.0;
+
+const CARET_WIDTH: f32 = 1.5;
+
+/// Longest wait between the clicks of a double or triple click.
+pub(crate) const MULTI_CLICK_INTERVAL: f64 = 0.4;
+
+/// Whether the modifier which moves by words is down.
+pub(crate) fn word_modifier(mods: &KeyboardModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        mods.alt
+    } else {
+        mods.control
+    }
+}
+
+/// Whether the modifier for shortcuts like select all is down.
+pub(crate) fn shortcut_modifier(mods: &KeyboardModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        mods.command
+    } else {
+        mods.control
+    }
+}
+
+/// View-model for `text_field` and `text_editor`.
 struct TextEditorState {
-    cursor: usize,
-    glyph_rects: Vec<Rect>,
-    lines: Vec<LineMetrics>,
+    editor: PlainEditor<ColorBrush>,
+
+    /// Width of the view from the last layout.
+    width: f64,
+
+    /// How far a single-line field has scrolled to keep the caret visible.
+    scroll_x: f64,
+
+    /// Clock time the caret last moved, which its blinking starts from.
+    blink_start: f64,
+
+    /// Whether the caret was showing at the last frame.
+    caret_shown: bool,
+
+    /// Clicks in a row, and the clock time of the last one.
+    clicks: usize,
+    last_click: f64,
 }
 
 impl TextEditorState {
-    fn fwd(&mut self, len: usize) {
-        self.cursor += 1;
-        if self.cursor > len {
-            self.cursor = len;
+    fn new(font_size: f32, family: Option<String>) -> Self {
+        let mut editor = PlainEditor::new(font_size);
+        let styles = editor.edit_styles();
+        match family {
+            Some(family) => {
+                styles.insert(FontStack::Source(Cow::Owned(format!("{family}, system-ui"))).into())
+            }
+            None => styles.insert(GenericFamily::SystemUi.into()),
+        };
+        styles.insert(StyleProperty::LineHeight(1.2));
+        styles.insert(StyleProperty::Brush(ColorBrush { color: TEXT_COLOR }));
+        Self {
+            editor,
+            width: 0.0,
+            scroll_x: 0.0,
+            blink_start: 0.0,
+            caret_shown: true,
+            clicks: 0,
+            last_click: f64::NEG_INFINITY,
         }
     }
-    fn back(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
+
+    /// Shows or hides the caret for the time `now`. Returns whether it changed,
+    /// and the seconds until it next changes.
+    fn blink(&mut self, now: f64) -> (bool, f64) {
+        let half = CARET_BLINK_PERIOD / 2.0;
+        let elapsed = (now - self.blink_start).max(0.0);
+        let shown = elapsed % CARET_BLINK_PERIOD < half;
+        let changed = shown != self.caret_shown;
+        self.caret_shown = shown;
+        (changed, half - elapsed % half)
+    }
+}
+
+impl Default for TextEditorState {
+    fn default() -> Self {
+        Self::new(Text::DEFAULT_SIZE, None)
+    }
+}
+
+/// Struct for `text_field` and `text_editor`.
+#[derive(Clone)]
+pub struct TextEditor<B> {
+    text: B,
+    multiline: bool,
+    placeholder: String,
+    font_size: f32,
+}
+
+impl<B> TextEditor<B>
+where
+    B: Binding<String>,
+{
+    /// Text shown in gray while there's nothing typed.
+    pub fn placeholder(self, placeholder: &str) -> Self {
+        Self {
+            placeholder: placeholder.into(),
+            ..self
         }
     }
 
-    fn find_line(&self) -> usize {
-        let mut i = 0;
-        for line in &self.lines {
-            if self.cursor >= line.glyph_start && self.cursor < line.glyph_end {
-                break;
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Takes the editor state out of the context, picking up any
+    /// changes made to the binding by other views.
+    fn take_state(&self, vid: ViewId, ctx: &mut Context) -> TextEditorState {
+        let font_size = self.font_size;
+        let family = ctx.init_env(&DefaultFont::default).0;
+        ctx.init_state(vid, &move || {
+            TextEditorState::new(font_size, family.clone())
+        });
+        let mut s = std::mem::take(ctx.state_mut::<TextEditorState>(vid));
+
+        if !s.editor.is_composing() {
+            let text = self.text.get(ctx);
+            if s.editor.text() != text.as_str() {
+                s.editor.set_text(text);
+                s.editor
+                    .driver(&mut ctx.font_ctx, &mut LayoutContext::new())
+                    .move_to_text_end();
             }
-            i += 1;
         }
-        i
+        s
     }
 
-    fn closest_in_range(&self, p: Point, range: std::ops::Range<usize>, rects: &[Rect]) -> usize {
-        let mut d = std::f32::MAX;
-        let mut closest = 0;
-        for i in range {
-            let dp = rects[i].center().distance_to(p);
-            if dp < d {
-                closest = i;
-                d = dp;
-            }
+    /// Puts the editor state back, writing any edits to the binding.
+    fn put_state(&self, vid: ViewId, ctx: &mut Context, s: TextEditorState) {
+        if !s.editor.is_composing() && s.editor.text() != self.text.get(ctx).as_str() {
+            let text = s.editor.text().to_string();
+            self.text.with_mut(ctx, |t| *t = text);
         }
-        closest
+        *ctx.state_mut::<TextEditorState>(vid) = s;
     }
 
-    fn down(&mut self) {
-        let p = self.glyph_rects[self.cursor].center();
+    /// Text inserted by typing or the input method. Fields stay on one line.
+    fn insertable(&self, text: &str) -> String {
+        if self.multiline {
+            text.into()
+        } else {
+            text.replace(['\r', '\n'], " ")
+        }
+    }
 
-        let line = self.find_line() + 1;
-        if line < self.lines.len() {
-            let metrics = self.lines[line];
-            self.cursor =
-                self.closest_in_range(p, metrics.glyph_start..metrics.glyph_end, &self.glyph_rects);
+    /// Handles a key press. Returns whether the key did anything.
+    fn key(
+        &self,
+        driver: &mut PlainEditorDriver<ColorBrush>,
+        key: &Key,
+        mods: &KeyboardModifiers,
+    ) -> bool {
+        let shift = mods.shift;
+        let word = word_modifier(mods);
+        // Command-arrows move to the ends of lines on macOS.
+        let line = cfg!(target_os = "macos") && mods.command;
+        match key {
+            Key::ArrowLeft => match (line, word, shift) {
+                (true, _, true) => driver.select_to_line_start(),
+                (true, _, false) => driver.move_to_line_start(),
+                (_, true, true) => driver.select_word_left(),
+                (_, true, false) => driver.move_word_left(),
+                (_, _, true) => driver.select_left(),
+                _ => driver.move_left(),
+            },
+            Key::ArrowRight => match (line, word, shift) {
+                (true, _, true) => driver.select_to_line_end(),
+                (true, _, false) => driver.move_to_line_end(),
+                (_, true, true) => driver.select_word_right(),
+                (_, true, false) => driver.move_word_right(),
+                (_, _, true) => driver.select_right(),
+                _ => driver.move_right(),
+            },
+            Key::ArrowUp if self.multiline => match (line, shift) {
+                (true, true) => driver.select_to_text_start(),
+                (true, false) => driver.move_to_text_start(),
+                (_, true) => driver.select_up(),
+                _ => driver.move_up(),
+            },
+            Key::ArrowDown if self.multiline => match (line, shift) {
+                (true, true) => driver.select_to_text_end(),
+                (true, false) => driver.move_to_text_end(),
+                (_, true) => driver.select_down(),
+                _ => driver.move_down(),
+            },
+            Key::Home => match (mods.control, shift) {
+                (true, true) => driver.select_to_text_start(),
+                (true, false) => driver.move_to_text_start(),
+                (_, true) => driver.select_to_line_start(),
+                _ => driver.move_to_line_start(),
+            },
+            Key::End => match (mods.control, shift) {
+                (true, true) => driver.select_to_text_end(),
+                (true, false) => driver.move_to_text_end(),
+                (_, true) => driver.select_to_line_end(),
+                _ => driver.move_to_line_end(),
+            },
+            Key::Backspace if word => driver.backdelete_word(),
+            Key::Backspace => driver.backdelete(),
+            Key::Delete if word => driver.delete_word(),
+            Key::Delete => driver.delete(),
+            Key::Enter if self.multiline => driver.insert_or_replace_selection("\n"),
+            Key::Space => driver.insert_or_replace_selection(" "),
+            Key::Character(c) if shortcut_modifier(mods) => {
+                if c.eq_ignore_ascii_case(&'a') {
+                    driver.select_all()
+                } else {
+                    return false;
+                }
+            }
+            Key::Character(c) => driver.insert_or_replace_selection(&c.to_string()),
+            _ => return false,
         }
+        true
     }
 
-    fn up(&mut self) {
-        let p = self.glyph_rects[self.cursor].center();
+    /// Handles the cut, copy and paste shortcuts. Returns whether
+    /// the text or selection changed.
+    fn clipboard(
+        &self,
+        c: char,
+        ctx: &mut Context,
+        s: &mut TextEditorState,
+        layout_cx: &mut LayoutContext<ColorBrush>,
+    ) -> bool {
+        let c = c.to_ascii_lowercase();
+        if c == 'v' {
+            let Some(pasted) = ctx.clipboard_get_text() else {
+                return false;
+            };
+            s.editor
+                .driver(&mut ctx.font_ctx, layout_cx)
+                .insert_or_replace_selection(&self.insertable(&pasted.text));
+            return true;
+        }
 
-        let line = self.find_line();
-        if line > 0 {
-            let metrics = self.lines[line - 1];
-            self.cursor =
-                self.closest_in_range(p, metrics.glyph_start..metrics.glyph_end, &self.glyph_rects);
+        let Some(selected) = s.editor.selected_text() else {
+            return false;
+        };
+        ctx.clipboard_set_text(selected);
+        if c == 'x' {
+            s.editor.driver(&mut ctx.font_ctx, layout_cx).delete();
+            return true;
         }
+        false
     }
 
-    fn key(&mut self, k: &Key, text: String) -> String {
-        match k {
-            Key::ArrowLeft => {
-                self.back();
-                text
+    /// Gives up the keyboard focus.
+    fn blur(&self, vid: ViewId, ctx: &mut Context, s: &mut TextEditorState) {
+        if s.editor.is_composing() {
+            s.editor
+                .driver(&mut ctx.font_ctx, &mut LayoutContext::new())
+                .clear_compose();
+        }
+        if ctx.focused_id == Some(vid) {
+            ctx.focused_id = None;
+        }
+        if ctx.ime_area.is_some_and(|(id, _)| id == vid) {
+            ctx.ime_area = None;
+        }
+        ctx.set_dirty();
+    }
+}
+
+impl<B> DynView for TextEditor<B>
+where
+    B: Binding<String>,
+{
+    fn process(
+        &self,
+        event: &Event,
+        path: &mut IdPath,
+        ctx: &mut Context,
+        _actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        let vid = ctx.view_id(path);
+        let rect = ctx.get_layout(path).rect;
+        let mut s = self.take_state(vid, ctx);
+        let focused = ctx.focused_id == Some(vid);
+        let mut layout_cx = LayoutContext::new();
+
+        // Point in the text's coordinates.
+        let text_point = |p: Point, s: &TextEditorState| ((p.x + s.scroll_x) as f32, p.y as f32);
+
+        let mut edited = true;
+        match event {
+            Event::TouchBegin { id, position } => {
+                if rect.contains(*position) {
+                    ctx.focused_id = Some(vid);
+                    ctx.capture_touch(*id, vid);
+
+                    let now = ctx.now();
+                    s.clicks = if now - s.last_click < MULTI_CLICK_INTERVAL {
+                        s.clicks + 1
+                    } else {
+                        1
+                    };
+                    s.last_click = now;
+
+                    let (x, y) = text_point(*position, &s);
+                    let mut driver = s.editor.driver(&mut ctx.font_ctx, &mut layout_cx);
+                    if driver.editor.is_composing() {
+                        driver.clear_compose();
+                    }
+                    match s.clicks {
+                        1 if ctx.key_mods.shift => driver.extend_selection_to_point(x, y),
+                        1 => driver.move_to_point(x, y),
+                        2 => driver.select_word_at_point(x, y),
+                        _ => driver.select_line_at_point(x, y),
+                    }
+                } else if focused {
+                    self.blur(vid, ctx, &mut s);
+                    edited = false;
+                } else {
+                    edited = false;
+                }
             }
-            Key::ArrowRight => {
-                self.fwd(text.len());
-                text
+            Event::TouchMove { id, position, .. } if ctx.has_touch(*id, vid) => {
+                let (x, y) = text_point(*position, &s);
+                s.editor
+                    .driver(&mut ctx.font_ctx, &mut layout_cx)
+                    .extend_selection_to_point(x, y);
             }
-            Key::ArrowUp => {
-                self.up();
-                text
+            // The mouse is touch 0.
+            Event::PointerMove { position, .. } if ctx.has_touch(0, vid) => {
+                let (x, y) = text_point(*position, &s);
+                s.editor
+                    .driver(&mut ctx.font_ctx, &mut layout_cx)
+                    .extend_selection_to_point(x, y);
             }
-            Key::ArrowDown => {
-                self.down();
-                text
+            Event::TouchEnd { id, .. } => {
+                ctx.release_touch(*id, vid);
+                edited = false;
             }
-            Key::Backspace => {
-                if self.cursor > 0 {
-                    let mut t = text;
-                    t.remove(self.cursor - 1);
-                    self.back();
-                    t
+            Event::Key(Key::Escape) if focused => {
+                self.blur(vid, ctx, &mut s);
+                edited = false;
+            }
+            // Keys go to the input method while it's composing.
+            Event::Key(Key::Character(c))
+                if focused && !s.editor.is_composing() && shortcut_modifier(&ctx.key_mods) =>
+            {
+                edited = match c.to_ascii_lowercase() {
+                    'c' | 'x' | 'v' => self.clipboard(*c, ctx, &mut s, &mut layout_cx),
+                    _ => {
+                        let mods = ctx.key_mods;
+                        self.key(
+                            &mut s.editor.driver(&mut ctx.font_ctx, &mut layout_cx),
+                            &Key::Character(*c),
+                            &mods,
+                        )
+                    }
+                };
+            }
+            Event::Key(key) if focused && !s.editor.is_composing() => {
+                let mods = ctx.key_mods;
+                edited = self.key(
+                    &mut s.editor.driver(&mut ctx.font_ctx, &mut layout_cx),
+                    key,
+                    &mods,
+                );
+            }
+            Event::ImePreedit { text, cursor } if focused => {
+                let mut driver = s.editor.driver(&mut ctx.font_ctx, &mut layout_cx);
+                if text.is_empty() {
+                    driver.clear_compose();
                 } else {
-                    text
+                    driver.set_compose(&self.insertable(text), *cursor);
                 }
             }
-            Key::Character(c) => {
-                let mut t = text;
-                t.insert_str(self.cursor, &format!("{}", c));
-                self.cursor += 1;
-                t
+            Event::ImeCommit(text) if focused => {
+                let mut driver = s.editor.driver(&mut ctx.font_ctx, &mut layout_cx);
+                driver.clear_compose();
+                driver.insert_or_replace_selection(&self.insertable(text));
+            }
+            Event::Anim => {
+                edited = false;
+                if focused {
+                    let (changed, next) = s.blink(ctx.now());
+                    if changed {
+                        ctx.set_dirty();
+                    }
+                    ctx.request_frame_after(next);
+                }
             }
-            Key::Space => {
-                let mut t = text;
-                t.insert(self.cursor, ' ');
-                self.cursor += 1;
-                t
+            _ => edited = false,
+        }
+
+        if edited {
+            s.blink_start = ctx.now();
+            s.caret_shown = true;
+            ctx.set_dirty();
+        }
+
+        if ctx.focused_id == Some(vid) {
+            s.editor.refresh_layout(&mut ctx.font_ctx, &mut layout_cx);
+
+            // Keep the caret in view.
+            if !self.multiline {
+                if let Some(caret) = s.editor.cursor_geometry(CARET_WIDTH) {
+                    if caret.x1 - s.scroll_x > s.width {
+                        s.scroll_x = caret.x1 - s.width;
+                    }
+                    if caret.x0 < s.scroll_x {
+                        s.scroll_x = caret.x0;
+                    }
+                }
             }
-            Key::Home => {
-                self.cursor = 0;
-                text
+
+            let origin = ctx.layout_rect(path).unwrap_or_default().origin();
+            let offset = origin.to_vec2() - Vec2::new(s.scroll_x, 0.0);
+            ctx.ime_area = Some((vid, s.editor.ime_cursor_area() + offset));
+        } else if ctx.ime_area.is_some_and(|(id, _)| id == vid) {
+            // Another view took the focus.
+            ctx.ime_area = None;
+        }
+
+        self.put_state(vid, ctx, s);
+    }
+
+    fn draw(&self, path: &mut IdPath, ctx: &mut Context) -> Scene {
+        let vid = ctx.view_id(path);
+        let rect = ctx.get_layout(path).rect;
+        let mut s = self.take_state(vid, ctx);
+        let focused = ctx.focused_id == Some(vid);
+        s.editor
+            .refresh_layout(&mut ctx.font_ctx, &mut LayoutContext::new());
+
+        let mut scene = Scene::new();
+        let transform = Affine::translate((-s.scroll_x, 0.0));
+
+        if focused {
+            for selection in s.editor.selection_geometry() {
+                scene.fill(
+                    peniko::Fill::NonZero,
+                    transform,
+                    selection_color(),
+                    None,
+                    &selection,
+                );
             }
-            Key::End => {
-                self.cursor = text.len();
-                text
+        }
+
+        if s.editor.text() == "" && !s.editor.is_composing() {
+            scene.append(
+                &draw_text(
+                    &self.placeholder,
+                    self.font_size,
+                    Vec2::ZERO,
+                    self.multiline.then_some(s.width as f32),
+                    MEDIUM_GRAY,
+                    &mut ctx.font_ctx,
+                ),
+                None,
+            );
+        } else if let Some(layout) = s.editor.try_layout() {
+            scene.append(&draw_layout(layout, transform), None);
+        }
+
+        if focused && s.caret_shown {
+            if let Some(caret) = s.editor.cursor_geometry(CARET_WIDTH) {
+                scene.fill(peniko::Fill::NonZero, transform, TEXT_COLOR, None, &caret);
             }
-            _ => text,
+        }
+
+        self.put_state(vid, ctx, s);
+
+        // Fields scroll sideways, so clip what's out of view.
+        if self.multiline {
+            scene
+        } else {
+            clip_scene(rect, &scene, Affine::IDENTITY)
         }
     }
-}
 
-impl TextEditorState {
-    fn new() -> Self {
-        Self {
-            cursor: 0,
-            glyph_rects: vec![],
-            lines: vec![],
+    fn layout(&self, path: &mut IdPath, args: &mut LayoutArgs) -> Size {
+        let vid = args.ctx.view_id(path);
+        let mut s = self.take_state(vid, args.ctx);
+
+        let mut layout_cx = LayoutContext::new();
+        let line_height = self.font_size as f64 * 1.2;
+        s.editor
+            .set_width(self.multiline.then_some(args.size.width as f32));
+        let layout = s.editor.layout(&mut args.ctx.font_ctx, &mut layout_cx);
+        let width = if args.size.width.is_finite() {
+            args.size.width
+        } else {
+            layout.full_width() as f64
+        };
+        let height = if self.multiline {
+            (layout.height() as f64).max(line_height)
+        } else {
+            line_height
+        };
+        s.width = width;
+        self.put_state(vid, args.ctx, s);
+
+        let size = Size::new(width, height);
+        args.ctx.update_layout(
+            path,
+            LayoutBox {
+                rect: Rect::from_origin_size(Point::ZERO, size),
+                offset: Vec2::ZERO,
+            },
+        );
+        size
+    }
+
+    fn hittest(&self, path: &mut IdPath, pt: Point, ctx: &mut Context) -> Option<ViewId> {
+        if ctx.get_layout(path).rect.contains(pt) {
+            Some(ctx.view_id(path))
+        } else {
+            None
         }
     }
+
+    fn gc(&self, path: &mut IdPath, ctx: &mut Context, map: &mut Vec<ViewId>) {
+        map.push(ctx.view_id(path));
+    }
 }
 
-/// A multi-line text editor.
+/// A single-line text field, which edits the string in a binding.
 ///
-/// This shows how a complex View with internal
-/// state can be created from more atomic Views.
-pub fn text_editor(text: impl Binding<String>) -> impl View {
-    focus(move |has_focus| {
-        state(TextEditorState::new, move |state, ctx| {
-            let cursor = ctx[state].cursor;
-            canvas(move |ctx, rect, vger| {
-                vger.translate([0.0, rect.height()]);
-                let font_size = 18;
-                let break_width = Some(rect.width());
-
-                vger.text(text.get(ctx), font_size, TEXT_COLOR, break_width);
-
-                if has_focus {
-                    let rects = vger.glyph_positions(text.get(ctx), font_size, break_width);
-                    let lines = vger.line_metrics(text.get(ctx), font_size, break_width);
-                    let glyph_rect_paint = vger.color_paint(vger::Color::MAGENTA);
-                    let p = if cursor == rects.len() {
-                        if let Some(r) = rects.last() {
-                            [r.origin.x + r.size.width, r.origin.y].into()
-                        } else {
-                            [0.0, -20.0].into()
-                        }
-                    } else {
-                        rects[cursor].origin
-                    };
-                    vger.fill_rect(Rect::new(p, [2.0, 20.0].into()), 0.0, glyph_rect_paint);
+/// Tap to focus it. Supports selecting with the mouse and keyboard,
+/// moving by words and lines, and input methods.
+///
+/// ```no_run
+/// # use vui::*;
+/// state(String::new, |name, _| text_field(name).placeholder("Name"));
+/// ```
+pub fn text_field<B: Binding<String>>(text: B) -> TextEditor<B> {
+    TextEditor {
+        text,
+        multiline: false,
+        placeholder: String::new(),
+        font_size: Text::DEFAULT_SIZE,
+    }
+}
 
-                    ctx[state].glyph_rects = rects;
-                    ctx[state].lines = lines;
-                }
-            })
-            .key(move |ctx, k| {
-                if has_focus {
-                    let t = text.with(ctx, |t| t.clone());
-                    let new_t = ctx[state].key(&k, t);
-                    text.with_mut(ctx, |t| *t = new_t);
-                }
-            })
-        })
-    })
+/// A multi-line text editor, which wraps to its width.
+pub fn text_editor<B: Binding<String>>(text: B) -> TextEditor<B> {
+    TextEditor {
+        multiline: true,
+        ..text_field(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn field() -> impl View {
+        state(String::new, |text, _| text_field(text))
+    }
+
+    #[test]
+    fn test_text_field_typing() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        // Keys are ignored until the field has the focus.
+        harness.type_text("ignored");
+        assert_eq!(harness.cx()[s], "");
+
+        harness.tap_at((10.0, 5.0));
+        harness.type_text("hello world");
+        harness.key(Key::Backspace);
+        assert_eq!(harness.cx()[s], "hello worl");
+
+        // Tapping elsewhere gives up the focus.
+        harness.tap_at((10.0, 90.0));
+        harness.type_text("d");
+        assert_eq!(harness.cx()[s], "hello worl");
+    }
+
+    #[test]
+    fn test_caret_blink_wakes_up() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        harness.update();
+        assert_eq!(harness.cx().next_frame_in(), None);
+
+        // Only a focused field needs frames, and only when the caret blinks.
+        harness.tap_at((10.0, 5.0));
+        let wait = harness.cx().next_frame_in().unwrap();
+        assert!(wait > 0.4 && wait <= CARET_BLINK_PERIOD / 2.0);
+        assert!(!harness.update());
+
+        // The field keeps asking while it has the focus.
+        harness.advance(wait);
+        assert!(harness.cx().next_frame_in().is_some());
+
+        // Once it loses the focus, the last blink asked for is the last frame.
+        harness.tap_at((10.0, 90.0));
+        harness.advance(CARET_BLINK_PERIOD / 2.0);
+        assert_eq!(harness.cx().next_frame_in(), None);
+    }
+
+    #[test]
+    fn test_text_field_selection() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        harness.tap_at((10.0, 5.0));
+        harness.type_text("hello world");
+
+        harness.cx_mut().key_mods.shift = true;
+        harness.key(Key::Home);
+        harness.cx_mut().key_mods.shift = false;
+        harness.type_text("bye");
+        assert_eq!(harness.cx()[s], "bye");
+
+        // Select all and delete.
+        if cfg!(target_os = "macos") {
+            harness.cx_mut().key_mods.command = true;
+        } else {
+            harness.cx_mut().key_mods.control = true;
+        }
+        harness.key(Key::Character('a'));
+        harness.cx_mut().key_mods = KeyboardModifiers::default();
+        harness.key(Key::Delete);
+        assert_eq!(harness.cx()[s], "");
+    }
+
+    #[test]
+    fn test_text_field_clipboard() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+        let shortcut = |harness: &mut TestHarness<_>, c| {
+            let mut mods = KeyboardModifiers::default();
+            if cfg!(target_os = "macos") {
+                mods.command = true;
+            } else {
+                mods.control = true;
+            }
+            harness.cx_mut().key_mods = mods;
+            harness.key(Key::Character(c));
+            harness.cx_mut().key_mods = KeyboardModifiers::default();
+        };
+
+        harness.tap_at((10.0, 5.0));
+        harness.type_text("hello");
+        shortcut(&mut harness, 'a');
+        shortcut(&mut harness, 'x');
+        assert_eq!(harness.cx()[s], "");
+        assert_eq!(
+            harness.cx().clipboard_get_text(),
+            Some(ClipboardText::from("hello"))
+        );
+
+        shortcut(&mut harness, 'v');
+        shortcut(&mut harness, 'v');
+        assert_eq!(harness.cx()[s], "hellohello");
+
+        // Fields paste new lines as spaces.
+        harness.cx_mut().clipboard_set_text("a\nb");
+        shortcut(&mut harness, 'v');
+        assert_eq!(harness.cx()[s], "hellohelloa b");
+    }
+
+    #[test]
+    fn test_text_field_binding_changes() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        harness.cx_mut()[s] = "abc".into();
+        harness.update();
+        harness.tap_at((150.0, 5.0));
+        harness.type_text("d");
+        assert_eq!(harness.cx()[s], "abcd");
+    }
+
+    #[test]
+    fn test_text_field_ime() {
+        let mut harness = TestHarness::new(field(), (200.0, 100.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        harness.tap_at((10.0, 5.0));
+        harness.type_text("a");
+
+        // Preedit text isn't part of the value until it's committed.
+        harness.process(&Event::ImePreedit {
+            text: "nihon".into(),
+            cursor: Some((5, 5)),
+        });
+        assert_eq!(harness.cx()[s], "a");
+        assert!(harness.cx().ime_area.is_some());
+
+        harness.process(&Event::ImePreedit {
+            text: String::new(),
+            cursor: None,
+        });
+        harness.process(&Event::ImeCommit("日本".into()));
+        assert_eq!(harness.cx()[s], "a日本");
+
+        harness.key(Key::Escape);
+        assert!(harness.cx().ime_area.is_none());
+    }
+
+    #[test]
+    fn test_text_editor_newlines() {
+        let ui = state(String::new, |text, _| {
+            vstack((text_field(text), text_editor(text)))
+        });
+        let mut harness = TestHarness::new(ui, (200.0, 200.0));
+        let s = harness.state_handle::<String>(&vec![0]);
+
+        // Only the editor takes new lines.
+        let field = harness.find_view_rect(&vec![0, 0, 0]).unwrap();
+        harness.tap_at(field.center());
+        harness.key(Key::Enter);
+        harness.process(&Event::ImeCommit("a\nb".into()));
+        assert_eq!(harness.cx()[s], "a b");
+
+        let editor = harness.find_view_rect(&vec![0, 0, 1]).unwrap();
+        harness.tap_at(editor.center());
+        harness.key(Key::Enter);
+        assert_eq!(harness.cx()[s], "a b\n");
+    }
 }
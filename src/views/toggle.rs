@@ -1,6 +1,5 @@
-use vello::peniko::color::palette::css::CYAN;
-
 use crate::*;
+use std::time::Duration;
 
 pub struct Toggle;
 
@@ -15,7 +14,7 @@ pub struct ToggleConfig {
     width: f64,
     height: f64,
     edge: f64,
-    animation_speed: f64,
+    animation: Animation,
     background_on: Color,
     background_off: Color,
     knob_color: Color,
@@ -27,7 +26,7 @@ impl Default for ToggleConfig {
             width: 35.0,
             height: 20.0,
             edge: 1.0,
-            animation_speed: 15.0,
+            animation: Animation::ease_out(Duration::from_millis(150)),
             background_on: AZURE_HIGHLIGHT_BACKGROUND,
             background_off: CONTROL_BACKGROUND,
             knob_color: MEDIUM_GRAY,
@@ -51,8 +50,9 @@ impl ToggleConfig {
         self
     }
 
-    pub fn animation_speed(mut self, speed: f64) -> Self {
-        self.animation_speed = speed.max(0.0); // Prevent negative animation speed
+    /// How the knob and background move when the toggle changes.
+    pub fn animation(mut self, animation: impl Into<Animation>) -> Self {
+        self.animation = animation.into();
         self
     }
 
@@ -73,47 +73,32 @@ pub fn toggle_with_config(on: impl Binding<bool>, config: ToggleConfig) -> impl
     let knob_size = config.height - double_edge;
     let travel_distance = config.width - config.height;
 
-    state(
-        || 0.0f64,
-        move |animation: StateHandle<f64>, ctx| {
-            let is_on = *on.get(ctx);
-
-            let animation_speed = config.animation_speed;
-
-            zstack((
-                // Background rectangle
-                rectangle()
-                    .color(if is_on {
-                        config.background_on
-                    } else {
-                        config.background_off
-                    })
-                    .corner_radius(config.height / 2.0) // Make corners perfectly round
-                    .sized((config.width, config.height))
-                    .tap(move |ctx| {
-                        on.with_mut(ctx, |b| *b = !*b);
-                    }),
-                // .hover_cursor(CursorStyle::Pointer), // Add pointer cursor on hover
-                // Knob circle
-                circle()
-                    .color(CYAN)
-                    .color(config.knob_color)
-                    .sized((knob_size, knob_size))
-                    .offset((ctx[animation] * travel_distance + config.edge, config.edge)), // .shadow(2.0, [0.0, 1.0], Color::BLACK.with_alpha(0.1)), // Add subtle shadow
-            ))
-            .anim(move |ctx, dt| {
-                let target = if is_on { 1.0 } else { 0.0 };
-                let delta = target - ctx[animation];
-
-                ctx[animation] += delta * animation_speed * dt;
-
-                // Snap to final position when very close
-                if delta.abs() < 0.01 {
-                    ctx[animation] = target;
-                }
-            })
-        },
-    )
+    with_ctx(move |ctx| {
+        let is_on = *on.get(ctx);
+        let knob_x = if is_on { travel_distance } else { 0.0 };
+
+        zstack((
+            // Background rectangle
+            rectangle()
+                .color(if is_on {
+                    config.background_on
+                } else {
+                    config.background_off
+                })
+                .corner_radius(config.height / 2.0) // Make corners perfectly round
+                .sized((config.width, config.height))
+                .tap(move |ctx| {
+                    on.with_mut(ctx, |b| *b = !*b);
+                }),
+            // .hover_cursor(CursorStyle::Pointer), // Add pointer cursor on hover
+            // Knob circle
+            circle()
+                .color(config.knob_color)
+                .sized((knob_size, knob_size))
+                .offset((knob_x + config.edge, config.edge)), // .shadow(2.0, [0.0, 1.0], Color::BLACK.with_alpha(0.1)), // Add subtle shadow
+        ))
+        .animation(config.animation, on)
+    })
 }
 
 /// A toggle switch with default configuration.
@@ -12,6 +12,9 @@ pub struct TouchInfo {
     /// The position of the touch in local space of the view that received the touch.
     pub pt: Point,
 
+    /// Index of the touch, so fingers can be told apart. The mouse is 0.
+    pub id: usize,
+
     /// The mouse button that was used for the touch if a mouse was used.
     pub button: Option<MouseButton>,
 
@@ -104,12 +107,12 @@ where
         let vid = ctx.view_id(path);
         match &event {
             Event::TouchBegin { id, position } => {
-                if self.hittest(path, *position, ctx).is_some() {
-                    ctx.touches[*id] = vid;
+                if self.hittest(path, *position, ctx).is_some() && ctx.capture_touch(*id, vid) {
                     self.func.call(
                         ctx,
                         TouchInfo {
                             pt: *position,
+                            id: *id,
                             button: ctx.mouse_button,
                             state: TouchState::Begin,
                         },
@@ -118,12 +121,12 @@ where
                 }
             }
             Event::TouchEnd { id, position } => {
-                if ctx.touches[*id] == vid {
-                    ctx.touches[*id] = ViewId::default();
+                if ctx.release_touch(*id, vid) {
                     self.func.call(
                         ctx,
                         TouchInfo {
                             pt: *position,
+                            id: *id,
                             button: ctx.mouse_button,
                             state: TouchState::End,
                         },
@@ -0,0 +1,328 @@
+use crate::*;
+use std::any::Any;
+use std::f64::consts::PI;
+
+/// Change in scale before two fingers are a pinch.
+const PINCH_THRESHOLD: f64 = 0.05;
+
+/// Change in angle, in radians, before two fingers are a rotation.
+const ROTATE_THRESHOLD: f64 = 0.1;
+
+#[derive(Default)]
+pub struct TwoFingerState {
+    /// Followed touches and where they are now.
+    fingers: Vec<(usize, Point)>,
+
+    /// Where the fingers were when the second one came down.
+    start: Option<(Point, Point)>,
+
+    /// Scale or angle so far.
+    value: f64,
+}
+
+impl TwoFingerState {
+    fn begin(&mut self, id: usize, position: Point) -> Option<Recognition> {
+        if self.fingers.len() >= 2 {
+            return None;
+        }
+        self.fingers.push((id, position));
+        if let [(_, a), (_, b)] = self.fingers[..] {
+            self.start = Some((a, b));
+        }
+        Some(Recognition::Possible)
+    }
+
+    /// Follows the fingers. Returns false once one has been lifted.
+    fn update(&mut self, event: &Event) -> bool {
+        match event {
+            Event::TouchMove { id, position, .. } => {
+                for finger in &mut self.fingers {
+                    if finger.0 == *id {
+                        finger.1 = *position;
+                    }
+                }
+                true
+            }
+            Event::TouchEnd { .. } => false,
+            _ => true,
+        }
+    }
+
+    /// Where the fingers started and where they are now, once both are down.
+    fn positions(&self) -> Option<((Point, Point), (Point, Point))> {
+        match (self.start, &self.fingers[..]) {
+            (Some(start), [(_, a), (_, b)]) => Some((start, (*a, *b))),
+            _ => None,
+        }
+    }
+}
+
+/// Angle of the line from `a` to `b`, counter-clockwise on screen.
+fn angle(a: Point, b: Point) -> f64 {
+    let v = b - a;
+    (-v.y).atan2(v.x)
+}
+
+/// Recognizes two fingers moving apart or together.
+///
+/// Also handles trackpad pinches.
+#[derive(Clone)]
+pub struct PinchRecognizer<F> {
+    func: F,
+}
+
+impl<F> PinchRecognizer<F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<A: 'static, F: Fn(&mut Context, f64, GestureState) -> A + Clone + 'static> Recognizer
+    for PinchRecognizer<F>
+{
+    type State = TwoFingerState;
+
+    fn begin(
+        &self,
+        state: &mut TwoFingerState,
+        id: usize,
+        position: Point,
+        _button: Option<MouseButton>,
+        _now: f64,
+    ) -> Option<Recognition> {
+        state.begin(id, position)
+    }
+
+    fn update(&self, state: &mut TwoFingerState, event: &Event, _now: f64) -> Recognition {
+        if !state.update(event) {
+            return Recognition::Reject;
+        }
+        match state.positions() {
+            Some(((a0, b0), (a1, b1))) => {
+                let d0 = (b0 - a0).hypot();
+                if d0 > 0.0 {
+                    state.value = (b1 - a1).hypot() / d0;
+                }
+                if (state.value - 1.0).abs() > PINCH_THRESHOLD {
+                    Recognition::Accept
+                } else {
+                    Recognition::Possible
+                }
+            }
+            None => Recognition::Possible,
+        }
+    }
+
+    fn recognized(
+        &self,
+        ctx: &mut Context,
+        state: &TwoFingerState,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        actions.push(Box::new((self.func)(ctx, state.value, GestureState::Began)))
+    }
+
+    fn changed(&self, ctx: &mut Context, state: &TwoFingerState, actions: &mut Vec<Box<dyn Any>>) {
+        actions.push(Box::new((self.func)(
+            ctx,
+            state.value,
+            GestureState::Changed,
+        )))
+    }
+
+    fn ended(&self, ctx: &mut Context, state: &TwoFingerState, actions: &mut Vec<Box<dyn Any>>) {
+        actions.push(Box::new((self.func)(ctx, state.value, GestureState::Ended)))
+    }
+
+    fn trackpad(
+        &self,
+        ctx: &mut Context,
+        state: &mut TwoFingerState,
+        event: &Event,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) -> bool {
+        if let Event::Pinch { delta, phase, .. } = event {
+            if *phase == GestureState::Began {
+                state.value = 1.0;
+            }
+            state.value *= 1.0 + delta;
+            actions.push(Box::new((self.func)(ctx, state.value, *phase)));
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Recognizes two fingers turning around each other.
+///
+/// Also handles trackpad rotation.
+#[derive(Clone)]
+pub struct RotateRecognizer<F> {
+    func: F,
+}
+
+impl<F> RotateRecognizer<F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<A: 'static, F: Fn(&mut Context, f64, GestureState) -> A + Clone + 'static> Recognizer
+    for RotateRecognizer<F>
+{
+    type State = TwoFingerState;
+
+    fn begin(
+        &self,
+        state: &mut TwoFingerState,
+        id: usize,
+        position: Point,
+        _button: Option<MouseButton>,
+        _now: f64,
+    ) -> Option<Recognition> {
+        state.begin(id, position)
+    }
+
+    fn update(&self, state: &mut TwoFingerState, event: &Event, _now: f64) -> Recognition {
+        if !state.update(event) {
+            return Recognition::Reject;
+        }
+        match state.positions() {
+            Some(((a0, b0), (a1, b1))) => {
+                let turned = angle(a1, b1) - angle(a0, b0);
+                state.value = (turned + PI).rem_euclid(2.0 * PI) - PI;
+                if state.value.abs() > ROTATE_THRESHOLD {
+                    Recognition::Accept
+                } else {
+                    Recognition::Possible
+                }
+            }
+            None => Recognition::Possible,
+        }
+    }
+
+    fn recognized(
+        &self,
+        ctx: &mut Context,
+        state: &TwoFingerState,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) {
+        actions.push(Box::new((self.func)(ctx, state.value, GestureState::Began)))
+    }
+
+    fn changed(&self, ctx: &mut Context, state: &TwoFingerState, actions: &mut Vec<Box<dyn Any>>) {
+        actions.push(Box::new((self.func)(
+            ctx,
+            state.value,
+            GestureState::Changed,
+        )))
+    }
+
+    fn ended(&self, ctx: &mut Context, state: &TwoFingerState, actions: &mut Vec<Box<dyn Any>>) {
+        actions.push(Box::new((self.func)(ctx, state.value, GestureState::Ended)))
+    }
+
+    fn trackpad(
+        &self,
+        ctx: &mut Context,
+        state: &mut TwoFingerState,
+        event: &Event,
+        actions: &mut Vec<Box<dyn Any>>,
+    ) -> bool {
+        if let Event::Rotate { delta, phase, .. } = event {
+            if *phase == GestureState::Began {
+                state.value = 0.0;
+            }
+            state.value += delta;
+            actions.push(Box::new((self.func)(ctx, state.value, *phase)));
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_pinch() {
+        let ui = state(
+            || vec![],
+            |scales, _| {
+                rectangle()
+                    .pinch(move |cx, scale, state| cx[scales].push((scale, state)))
+                    .rotate(move |cx, _, _| cx[scales].push((0.0, GestureState::Ended)))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (200.0, 200.0));
+        let s = harness.state_handle::<Vec<(f64, GestureState)>>(&vec![0]);
+
+        harness.touch_begin(1, (90.0, 100.0));
+        harness.touch_begin(2, (110.0, 100.0));
+        harness.touch_move(2, (120.0, 100.0));
+        harness.touch_move(1, (80.0, 100.0));
+        harness.touch_end(1, (80.0, 100.0));
+        harness.touch_end(2, (120.0, 100.0));
+
+        assert_eq!(
+            harness.cx()[s],
+            vec![
+                (1.5, GestureState::Began),
+                (2.0, GestureState::Changed),
+                (2.0, GestureState::Ended)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotate() {
+        let ui = state(
+            || vec![],
+            |angles, _| {
+                rectangle()
+                    .rotate(move |cx, angle, _| cx[angles].push(angle))
+                    .tap(move |cx| cx[angles].push(f64::NAN))
+            },
+        );
+        let mut harness = TestHarness::new(ui, (200.0, 200.0));
+        let s = harness.state_handle::<Vec<f64>>(&vec![0]);
+
+        // Turn a quarter turn counter-clockwise.
+        harness.touch_begin(1, (100.0, 100.0));
+        harness.touch_begin(2, (150.0, 100.0));
+        harness.touch_move(2, (100.0, 50.0));
+        harness.touch_end(2, (100.0, 50.0));
+        harness.touch_end(1, (100.0, 100.0));
+
+        let angles = harness.cx()[s].clone();
+        assert_eq!(angles.len(), 2);
+        assert!(angles.iter().all(|a| (a - PI / 2.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_trackpad_pinch() {
+        let ui = state(
+            || 1.0,
+            |zoom, _| rectangle().pinch(move |cx, scale, _| cx[zoom] = scale),
+        );
+        let mut harness = TestHarness::new(ui, (200.0, 200.0));
+        let s = harness.state_handle::<f64>(&vec![0]);
+
+        for (delta, phase) in [
+            (0.0, GestureState::Began),
+            (0.5, GestureState::Changed),
+            (1.0, GestureState::Ended),
+        ] {
+            harness.process(&Event::Pinch {
+                position: (100.0, 100.0).into(),
+                delta,
+                phase,
+            });
+        }
+        assert_eq!(harness.cx()[s], 3.0);
+    }
+}
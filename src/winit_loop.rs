@@ -1,22 +1,19 @@
 use crate::*;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use std::{
-    collections::{HashMap, VecDeque},
-    sync::Mutex,
-};
+use std::{collections::VecDeque, sync::Mutex};
 
-use std::time::Instant;
-use winit::event::{ElementState, MouseButton, TouchPhase, WindowEvent};
+use std::time::{Duration, Instant};
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
 use winit::keyboard::{Key, ModifiersState, NamedKey};
 use winit::{application::ApplicationHandler, event_loop::EventLoopProxy};
 
-use vello::peniko::color::palette;
+use vello::kurbo::Affine;
 use vello::util::{RenderContext, RenderSurface};
 use vello::{AaConfig, Renderer, RendererOptions};
 
-use winit::dpi::LogicalSize;
-use winit::event_loop::EventLoop;
+use winit::dpi::{LogicalPosition, LogicalSize};
+use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::{Window, WindowAttributes};
 
 use vello::wgpu;
@@ -30,6 +27,35 @@ lazy_static::lazy_static! {
     static ref GLOBAL_WORK_QUEUE: Mutex<WorkQueue> = Mutex::new(WorkQueue::new());
 }
 
+/// Call this function to do something on the main thread.
+///
+/// The closure is queued and the event loop is woken up. Queued closures
+/// run before the next `Context::update`, so they can safely modify state
+/// from background threads.
+pub fn on_main(f: impl FnOnce(&mut Context) + Send + 'static) {
+    GLOBAL_WORK_QUEUE.lock().unwrap().push_back(Box::new(f));
+    wake_event_loop();
+}
+
+/// Wakes up the event loop, if it's running.
+pub(crate) fn wake_event_loop() {
+    let opt_proxy = GLOBAL_EVENT_LOOP_PROXY.lock().unwrap();
+    if let Some(proxy) = &*opt_proxy {
+        if let Err(err) = proxy.send_event(()) {
+            log::warn!("error waking up event loop: {:?}", err);
+        }
+    }
+}
+
+/// Runs the closures queued by `on_main`.
+fn run_work_queue(cx: &mut Context) {
+    // Take the queue first so closures can call `on_main` themselves.
+    let queue = std::mem::take(&mut *GLOBAL_WORK_QUEUE.lock().unwrap());
+    for f in queue {
+        f(cx);
+    }
+}
+
 fn default_threads() -> usize {
     /// Default number of threads to use for initializing shaders.
     ///
@@ -63,17 +89,24 @@ where
     // Whilst suspended, we drop `render_state`, but need to keep the same window.
     // If render_state exists, we must store the window in it, to maintain drop order
     cached_window: Option<Arc<Window>>,
+    window_attributes: WindowAttributes,
 
     use_cpu: bool,
     num_init_threads: usize,
 
     scene: Scene,
 
+    // Developer overlay with frame statistics, and its parts which are showing.
+    dev_overlay: bool,
+    stats: FrameStats,
     stats_shown: bool,
+    graph_shown: bool,
 
-    base_color: Option<Color>,
+    // Keys for trying out the renderer, off unless the app turns them on.
+    demo_keys: bool,
+
+    base_color: Color,
 
-    complexity_shown: bool,
     vsync_on: bool,
 
     // We allow cycling through AA configs in either direction, so use a signed index
@@ -82,11 +115,32 @@ where
     modifiers: ModifiersState,
 
     title: Arc<str>,
+    // Pixels per point. Views lay out and receive events in points.
+    scale_factor: f64,
     mouse_position: Point,
+    // winit touch id held in each touch slot. Slot 0 is the mouse.
+    touch_slots: [Option<u64>; 16],
+    touch_positions: [Point; 16],
+    // Input method state last sent to the window.
+    ime_allowed: bool,
+    ime_area: Option<Rect>,
+    // Whether nothing is animating, so the loop sleeps until the next event.
+    idle: bool,
     cx: Context,
     view: T,
 }
 
+/// Points scrolled per line for mouse wheels which report lines.
+const SCROLL_LINE_HEIGHT: f64 = 20.0;
+
+fn gesture_state(phase: TouchPhase) -> GestureState {
+    match phase {
+        TouchPhase::Started => GestureState::Began,
+        TouchPhase::Moved => GestureState::Changed,
+        TouchPhase::Ended | TouchPhase::Cancelled => GestureState::Ended,
+    }
+}
+
 fn process_event(cx: &mut Context, view: &impl View, event: &Event, window: Arc<Window>) {
     cx.process(view, event);
 
@@ -119,11 +173,15 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
         let None = self.state else {
             return;
         };
-        let window = self
-            .cached_window
-            .take()
-            .unwrap_or_else(|| Arc::new(event_loop.create_window(window_attributes()).unwrap()));
+        let window = self.cached_window.take().unwrap_or_else(|| {
+            Arc::new(
+                event_loop
+                    .create_window(self.window_attributes.clone())
+                    .unwrap(),
+            )
+        });
         let size = window.inner_size();
+        self.scale_factor = window.scale_factor();
         let present_mode = if self.vsync_on {
             wgpu::PresentMode::AutoVsync
         } else {
@@ -135,6 +193,8 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
         // We need to block here, in case a Suspended event appeared
         let surface = pollster::block_on(surface_future).expect("Error creating surface");
         self.state = {
+            // Draw the first frame, since nothing else asks for one until an event arrives.
+            window.request_redraw();
             let render_state = RenderState { window, surface };
             self.renderers
                 .resize_with(self.context.devices.len(), || None);
@@ -174,7 +234,11 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
             return;
         }
         match event {
-            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::CloseRequested => {
+                if self.cx.close_requested(&self.view) {
+                    event_loop.exit();
+                }
+            }
             WindowEvent::ModifiersChanged(m) => {
                 self.modifiers = m.state();
                 self.cx.key_mods = KeyboardModifiers {
@@ -224,14 +288,20 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
                     };
 
                     if let (Some(key), ElementState::Pressed) = (key, event.state) {
-                        self.cx.process(&self.view, &Event::Key(key))
+                        self.cx.process_key(&self.view, key)
                     }
 
                     if let (Some(key), ElementState::Released) = (key, event.state) {
                         self.cx.process(&self.view, &Event::KeyReleased(key))
                     }
                 }
-                if event.state == ElementState::Pressed {
+                // Leave keys to a focused view, such as a text field being typed in,
+                // and to hotkeys for menu commands.
+                if self.demo_keys
+                    && event.state == ElementState::Pressed
+                    && !self.cx.event_handled
+                    && self.cx.focused_id.is_none()
+                {
                     match event.logical_key.as_ref() {
                         Key::Character(char) => {
                             // TODO: Have a more principled way of handling modifiers on keypress
@@ -242,7 +312,7 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
                                     self.stats_shown = !self.stats_shown;
                                 }
                                 "d" => {
-                                    self.complexity_shown = !self.complexity_shown;
+                                    self.graph_shown = !self.graph_shown;
                                 }
                                 "m" => {
                                     self.aa_config_ix = if self.modifiers.shift_key() {
@@ -264,38 +334,77 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
                                 }
                                 _ => {}
                             }
+                            render_state.window.request_redraw();
                         }
-                        Key::Named(NamedKey::Escape) => event_loop.exit(),
+                        Key::Named(NamedKey::Escape) => self.cx.quit(),
                         _ => {}
                     }
                 }
             }
+            WindowEvent::Ime(ime) => {
+                let event = match ime {
+                    Ime::Preedit(text, cursor) => Some(Event::ImePreedit { text, cursor }),
+                    Ime::Commit(text) => Some(Event::ImeCommit(text)),
+                    Ime::Enabled | Ime::Disabled => None,
+                };
+                if let Some(event) = event {
+                    self.cx.process(&self.view, &event);
+                }
+            }
             WindowEvent::Touch(winit::event::Touch {
-                phase, location, ..
+                phase,
+                location,
+                id,
+                ..
             }) => {
+                let location = location.to_logical::<f64>(self.scale_factor);
                 let position = (location.x, location.y).into();
 
-                let delta = position - self.cx.previous_position[0];
-
-                // TODO: Multi-Touch management
-                let event = match phase {
-                    TouchPhase::Started => Some(Event::TouchBegin { id: 0, position }),
-                    TouchPhase::Moved => Some(Event::TouchMove {
-                        id: 0,
-                        position,
-                        delta,
-                    }),
-                    TouchPhase::Ended | TouchPhase::Cancelled => {
-                        Some(Event::TouchEnd { id: 0, position })
+                let slot = match phase {
+                    TouchPhase::Started => {
+                        // Take the first free slot. Extra fingers are ignored.
+                        let slot = (1..self.touch_slots.len())
+                            .find(|&slot| self.touch_slots[slot].is_none());
+                        if let Some(slot) = slot {
+                            self.touch_slots[slot] = Some(id);
+                            self.touch_positions[slot] = position;
+                        }
+                        slot
                     }
+                    _ => self.touch_slots.iter().position(|s| *s == Some(id)),
                 };
 
-                let window = self.cached_window.as_ref().unwrap().clone();
+                let event = slot.map(|slot| {
+                    let delta = position - self.touch_positions[slot];
+                    self.touch_positions[slot] = position;
+                    match phase {
+                        TouchPhase::Started => Event::TouchBegin { id: slot, position },
+                        TouchPhase::Moved => Event::TouchMove {
+                            id: slot,
+                            position,
+                            delta,
+                        },
+                        TouchPhase::Ended | TouchPhase::Cancelled => {
+                            self.touch_slots[slot] = None;
+                            Event::TouchEnd { id: slot, position }
+                        }
+                    }
+                });
+
+                let window = render_state.window.clone();
 
                 if let Some(event) = event {
                     process_event(&mut self.cx, &self.view, &event, window);
                 }
             }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                // A `Resized` follows with the new physical size.
+                self.scale_factor = scale_factor;
+                render_state.window.request_redraw();
+            }
+            WindowEvent::Occluded(false) => {
+                render_state.window.request_redraw();
+            }
             WindowEvent::Resized(size) => {
                 if let Some(RenderState { surface, window }) = &mut self.state {
                     self.context
@@ -323,13 +432,16 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
 
                         self.cx.set_dirty();
 
-                        if let Some(window) = &self.cached_window {
-                            let event = Event::TouchBegin {
-                                id: 0,
-                                position: self.mouse_position,
-                            };
-                            process_event(&mut self.cx, &self.view, &event, window.clone());
-                        }
+                        let event = Event::TouchBegin {
+                            id: 0,
+                            position: self.mouse_position,
+                        };
+                        process_event(
+                            &mut self.cx,
+                            &self.view,
+                            &event,
+                            render_state.window.clone(),
+                        );
                     }
                     ElementState::Released => {
                         self.cx.mouse_button = None;
@@ -343,36 +455,112 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
 
                         self.cx.set_dirty();
 
-                        if let Some(window) = &self.cached_window {
-                            let event = Event::TouchEnd {
-                                id: 0,
-                                position: self.mouse_position,
-                            };
-                            process_event(&mut self.cx, &self.view, &event, window.clone())
-                        }
+                        let event = Event::TouchEnd {
+                            id: 0,
+                            position: self.mouse_position,
+                        };
+                        process_event(
+                            &mut self.cx,
+                            &self.view,
+                            &event,
+                            render_state.window.clone(),
+                        );
                     }
                 };
             }
             WindowEvent::CursorMoved { position, .. } => {
-                self.mouse_position = (position.x, position.y).into();
+                let position = position.to_logical::<f64>(self.scale_factor);
+                let position = Point::new(position.x, position.y);
+                let delta = position - self.mouse_position;
+                self.mouse_position = position;
+                let event = Event::PointerMove { position, delta };
+                process_event(
+                    &mut self.cx,
+                    &self.view,
+                    &event,
+                    render_state.window.clone(),
+                );
+            }
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        Vec2::new(x as f64, y as f64) * SCROLL_LINE_HEIGHT
+                    }
+                    MouseScrollDelta::PixelDelta(p) => {
+                        let p = p.to_logical::<f64>(self.scale_factor);
+                        Vec2::new(p.x, p.y)
+                    }
+                };
+                let event = Event::Scroll {
+                    position: self.mouse_position,
+                    delta,
+                    phase: gesture_state(phase),
+                };
+                process_event(
+                    &mut self.cx,
+                    &self.view,
+                    &event,
+                    render_state.window.clone(),
+                );
+            }
+            WindowEvent::PinchGesture { delta, phase, .. } => {
+                let event = Event::Pinch {
+                    position: self.mouse_position,
+                    delta,
+                    phase: gesture_state(phase),
+                };
+                process_event(
+                    &mut self.cx,
+                    &self.view,
+                    &event,
+                    render_state.window.clone(),
+                );
+            }
+            WindowEvent::RotationGesture { delta, phase, .. } => {
+                let event = Event::Rotate {
+                    position: self.mouse_position,
+                    delta: (delta as f64).to_radians(),
+                    phase: gesture_state(phase),
+                };
+                process_event(
+                    &mut self.cx,
+                    &self.view,
+                    &event,
+                    render_state.window.clone(),
+                );
+            }
+            WindowEvent::CursorLeft { .. } => {
+                process_event(
+                    &mut self.cx,
+                    &self.view,
+                    &Event::MouseLeftWindow,
+                    render_state.window.clone(),
+                );
             }
             WindowEvent::RedrawRequested => {
-                render_state.window.request_redraw();
-
                 let Some(RenderState { surface, .. }) = &self.state else {
                     return;
                 };
                 let width = surface.config.width;
                 let height = surface.config.height;
                 let device_handle = &self.context.devices[surface.dev_id];
-                self.scene = self
-                    .cx
-                    .render(&self.view, (width as _, height as _).into(), 1.0);
+                let size = Size::new(width as _, height as _) / self.scale_factor;
+                let frame_start = self.cx.now();
+                self.scene = self.cx.render(&self.view, size, self.scale_factor);
                 self.aa_config_ix = self.aa_config_ix.rem_euclid(AA_CONFIGS.len() as i32);
 
-                // If the user specifies a base color in the CLI we use that. Otherwise we use any
-                // color specified by the scene. The default is black.
-                let base_color = self.base_color.unwrap_or(palette::css::BLACK);
+                if self.dev_overlay && self.stats_shown {
+                    self.stats.frame(frame_start);
+                    let overlay = self.stats.draw(
+                        &mut self.cx,
+                        AA_CONFIGS[self.aa_config_ix as usize],
+                        self.graph_shown,
+                    );
+                    self.scene
+                        .append(&overlay, Some(Affine::scale(self.scale_factor)));
+                }
+
+                let base_color = self.base_color;
                 let antialiasing_method = AA_CONFIGS[self.aa_config_ix as usize];
                 let render_params = vello::RenderParams {
                     base_color,
@@ -381,6 +569,7 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
                     antialiasing_method,
                 };
 
+                let submit_start = self.cx.now();
                 self.renderers[surface.dev_id]
                     .as_mut()
                     .unwrap()
@@ -417,23 +606,71 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
                 {
                     device_handle.device.poll(wgpu::Maintain::Poll);
                 }
+                self.stats.submit = self.cx.now() - submit_start;
             }
             _ => {}
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
-        if let Some(window) = &self.cached_window {
-            let width = window.inner_size().width;
-            let height = window.inner_size().height;
-            if self.cx.update(&self.view, (width as _, height as _).into()) {
+    fn user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, _event: ()) {
+        // Sent by `on_main` to wake us up. The work queue is run in `about_to_wait`.
+    }
+
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        run_work_queue(&mut self.cx);
+
+        if self.cx.quit_requested {
+            event_loop.exit();
+            return;
+        }
+
+        let window = match &self.state {
+            Some(render_state) => Some(&render_state.window),
+            None => self.cached_window.as_ref(),
+        };
+        if let Some(window) = window {
+            let size = window.inner_size().to_logical::<f64>(self.scale_factor);
+            let update_start = self.cx.now();
+            let redraw = self.cx.update(&self.view, (size.width, size.height).into());
+            self.stats.update = self.cx.now() - update_start;
+            if redraw {
                 window.request_redraw();
             }
 
+            // Sleep until the next event unless a view asked for a frame later on.
+            // Redraws wake us up by themselves.
+            let control_flow = match self.cx.next_frame_in() {
+                Some(seconds) if !redraw => {
+                    ControlFlow::WaitUntil(Instant::now() + Duration::from_secs_f64(seconds))
+                }
+                _ => ControlFlow::Wait,
+            };
+            let idle = !redraw && control_flow == ControlFlow::Wait;
+            if idle && !self.idle {
+                log::debug!("idle after {} frames", self.cx.frames_drawn());
+            }
+            self.idle = idle;
+            event_loop.set_control_flow(control_flow);
+
             if self.cx.window_title != self.title {
                 self.title = self.cx.window_title.clone();
                 window.set_title(&self.cx.window_title);
             }
+
+            // Only ask for the input method while a text view is focused,
+            // so it doesn't swallow keys meant for other views.
+            let ime_area = self.cx.ime_area.map(|(_, area)| area);
+            if ime_area.is_some() != self.ime_allowed {
+                self.ime_allowed = ime_area.is_some();
+                window.set_ime_allowed(self.ime_allowed);
+            }
+            if let Some(area) = ime_area.filter(|area| self.ime_area != Some(*area)) {
+                window.set_ime_cursor_area(
+                    LogicalPosition::new(area.x0, area.y0),
+                    LogicalSize::new(area.width(), area.height()),
+                );
+            }
+            self.ime_area = ime_area;
         }
     }
 
@@ -447,28 +684,62 @@ impl<T: View> ApplicationHandler<()> for VelloApp<'_, T> {
     }
 }
 
-fn run(event_loop: EventLoop<()>, render_cx: RenderContext, view: impl View) {
-    let (render_state, renderers) = (None::<RenderState<'_>>, vec![]);
+pub(crate) fn run_app(config: App<impl View>) -> anyhow::Result<()> {
+    // TODO: initializing both env_logger and console_logger fails on wasm.
+    // Figure out a more principled approach.
+    #[cfg(not(target_arch = "wasm32"))]
+    if config.logging {
+        // Leave any logger the program has already set up in place.
+        let _ = env_logger::builder()
+            .format_timestamp(Some(env_logger::TimestampPrecision::Millis))
+            .filter_level(log::LevelFilter::Warn)
+            .try_init();
+    }
+    let event_loop = EventLoop::<()>::with_user_event().build()?;
+
+    let mut window_attributes = Window::default_attributes()
+        .with_inner_size(LogicalSize::new(config.size.width, config.size.height))
+        .with_resizable(config.resizable)
+        .with_title(config.title.as_str());
+    if let Some(min_size) = config.min_size {
+        window_attributes = window_attributes
+            .with_min_inner_size(LogicalSize::new(min_size.width, min_size.height));
+    }
+
+    let mut cx = Context::new();
+    cx.window_title = config.title.as_str().into();
 
-    let window_title = String::from("vui");
     let mut app = VelloApp {
-        context: render_cx,
-        renderers,
-        state: render_state,
+        context: RenderContext::new(),
+        renderers: vec![],
+        state: None,
         cached_window: None,
+        window_attributes,
         use_cpu: false,
         num_init_threads: default_threads(),
         scene: Scene::new(),
+        dev_overlay: config.dev_overlay,
+        stats: FrameStats::default(),
         stats_shown: true,
-        base_color: None,
-        complexity_shown: false,
-        vsync_on: true,
-
-        aa_config_ix: 0,
-        view,
-        title: window_title.into(),
+        graph_shown: true,
+        demo_keys: config.demo_keys,
+        base_color: config.background,
+        vsync_on: config.vsync,
+
+        aa_config_ix: AA_CONFIGS
+            .iter()
+            .position(|aa| *aa == config.antialiasing)
+            .unwrap_or(0) as i32,
+        view: config.view,
+        title: cx.window_title.clone(),
+        scale_factor: 1.0,
         mouse_position: Point::ZERO,
-        cx: Context::new(),
+        touch_slots: [None; 16],
+        touch_positions: [Point::ZERO; 16],
+        ime_allowed: false,
+        ime_area: None,
+        idle: false,
+        cx,
 
         modifiers: ModifiersState::default(),
     };
@@ -478,39 +749,13 @@ fn run(event_loop: EventLoop<()>, render_cx: RenderContext, view: impl View) {
         *GLOBAL_EVENT_LOOP_PROXY.lock().unwrap() = Some(event_loop.create_proxy());
     }
 
-    let mut commands: Vec<CommandInfo> = Vec::new();
-    let mut command_map = HashMap::new();
-    app.cx.commands(&app.view, &mut commands);
-
-    {
-        // So we can infer a type for CommandMap when winit is enabled.
-        command_map.insert("", "");
-    }
-
-    if let Err(e) = event_loop.run_app(&mut app) {
-        log::error!("Error exiting event loop: {:?}", e);
-    };
-}
+    event_loop.run_app(&mut app)?;
 
-fn window_attributes() -> WindowAttributes {
-    Window::default_attributes()
-        .with_inner_size(LogicalSize::new(1044, 800))
-        .with_resizable(true)
-        .with_title("Vello demo")
+    Ok(())
 }
 
+/// Runs a view in a window with the default settings. Use `App`
+/// to configure the window.
 pub fn vui(view: impl View) -> anyhow::Result<()> {
-    // TODO: initializing both env_logger and console_logger fails on wasm.
-    // Figure out a more principled approach.
-    #[cfg(not(target_arch = "wasm32"))]
-    env_logger::builder()
-        .format_timestamp(Some(env_logger::TimestampPrecision::Millis))
-        .filter_level(log::LevelFilter::Warn)
-        .init();
-    let event_loop = EventLoop::<()>::with_user_event().build()?;
-    let render_cx = RenderContext::new();
-
-    run(event_loop, render_cx, view);
-
-    Ok(())
+    App::new(view).run()
 }
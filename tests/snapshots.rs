@@ -0,0 +1,39 @@
+//! Golden-image tests for the built-in controls.
+//!
+//! These need a wgpu adapter (a software one such as lavapipe is fine) and
+//! reference images in `tests/snapshots`, so they are ignored by default.
+//! Run them with `cargo test --test snapshots -- --ignored`, and set
+//! `VUI_UPDATE_SNAPSHOTS=1` to record the reference images, then check them in.
+
+use vui::*;
+
+fn options() -> SnapshotOptions {
+    SnapshotOptions {
+        size: Size::new(200.0, 60.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+#[ignore = "needs a wgpu adapter and recorded references"]
+fn snapshot_button() {
+    assert_snapshot!(button("button", |_| ()), "button", options());
+}
+
+#[test]
+#[ignore = "needs a wgpu adapter and recorded references"]
+fn snapshot_hslider() {
+    assert_snapshot!(state(|| 0.25, |s, _| hslider(s)), "hslider", options());
+}
+
+#[test]
+#[ignore = "needs a wgpu adapter and recorded references"]
+fn snapshot_toggle_on() {
+    assert_snapshot!(state(|| true, |s, _| toggle(s)), "toggle_on", options());
+}
+
+#[test]
+#[ignore = "needs a wgpu adapter and recorded references"]
+fn snapshot_toggle_off() {
+    assert_snapshot!(state(|| false, |s, _| toggle(s)), "toggle_off", options());
+}